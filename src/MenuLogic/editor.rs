@@ -1,237 +1,274 @@
-use raylib::prelude::{ RaylibHandle, KeyboardKey };
+use raylib::prelude::{ RaylibHandle, KeyboardKey, Rectangle, Vector2 };
 
-use crate::types::{ EditorTab, ObjectStruct, Button };
+use crate::history::{ EditHistory, EditKind };
+use crate::objects::ObjectRegistry;
+use crate::spatial::ObjectColumns;
+use crate::types::{ EditorTab, ObjectStruct, Button, SelectMode };
 
 // PED stands for place, edit, delete
+// Selecting in the edit tab is done by select_objects
 pub fn object_ped(
     object_grid: &mut Vec<ObjectStruct>,
     active_tab: EditorTab,
     snapped_x: i32,
     snapped_y: i32,
     current_object: u32,
-    selected_object: &mut u16,
-
-    no_touch_toggle: &mut Button,
-    hide_toggle: &mut Button,
-    object_settings: &mut Button,
+    registry: &ObjectRegistry,
+    object_columns: &ObjectColumns,
+    history: &mut EditHistory,
     rl: &RaylibHandle
 ) {
     if active_tab == EditorTab::Build {
-        object_grid.push(ObjectStruct {
+        history.place(object_grid, ObjectStruct {
             y: if snapped_y < 0 { snapped_y - 40 } else { snapped_y },
             x: if snapped_x < 0 { snapped_x - 40 } else { snapped_x },
             no_touch: 0,
             hide: 0,
+            color_channel: 0,
             id: current_object,
             rotation: 0,
             selected: false,
-            properties: if current_object == 23 { Some(
-                vec![
-                    "50".to_string(),
-                    "50".to_string(),
-                    "50".to_string(),
-                    "1".to_string()
-                ]
-            )} else {
-                None
-            }
-        });
+            properties: registry.get(current_object)
+                .filter(|object_type| !object_type.properties.is_empty())
+                .map(|object_type| object_type.default_properties())
+        }, rl.get_time());
     } else if active_tab == EditorTab::Delete {
-        let mut obj_index = 0;
-        while obj_index < object_grid.len() {
-            if object_grid[obj_index].x == if snapped_x < 0 { snapped_x - 40 } else { snapped_x }
-            && object_grid[obj_index].y == if snapped_y < 0 { snapped_y - 40 } else { snapped_y } {
-                object_grid.remove(obj_index);
-                break;
-            } else {
-                obj_index += 1;
-            }
+        // Only the first object in the spot gets deleted
+        let cell_x = if snapped_x < 0 { snapped_x - 40 } else { snapped_x };
+        let cell_y = if snapped_y < 0 { snapped_y - 40 } else { snapped_y };
+        if let Some(obj_index) = object_columns.in_range(cell_x, cell_x)
+            .into_iter()
+            .find(|obj_index| object_grid[*obj_index].y == cell_y) {
+            history.delete_at(object_grid, obj_index, rl.get_time());
         }
-    } else if active_tab == EditorTab::Edit {
-        let mut obj_index = 0;
-        while obj_index < object_grid.len() {
-            if object_grid[obj_index].x == if snapped_x < 0 { snapped_x - 40 } else { snapped_x }
-            && object_grid[obj_index].y == if snapped_y < 0 { snapped_y - 40 } else { snapped_y }
-            && !object_grid[obj_index].selected {
-                if rl.is_key_up(KeyboardKey::KEY_LEFT_SHIFT) {
-                    let mut objj_index = 0;
-                    while objj_index < object_grid.len() {
-                        object_grid[objj_index].selected = false;
-                        objj_index += 1
-                    }
-                }
-
-                object_grid[obj_index].selected = true;
-                *selected_object = object_grid[obj_index].id as u16;
-
-                if object_grid[obj_index].no_touch == 1 {
-                    no_touch_toggle.is_disabled = false;
-                } else {
-                    no_touch_toggle.is_disabled = true;
-                }
-
-                if object_grid[obj_index].hide == 1 {
-                    hide_toggle.is_disabled = false;
-                } else {
-                    hide_toggle.is_disabled = true;
-                }
-
-                if object_grid[obj_index].id == 23 {
-                    object_settings.is_disabled = false
-                } else {
-                    object_settings.is_disabled = true
-                }
-                break;
-            } else {
-                obj_index += 1;
-            }
+    }
+}
+
+// Objects take up one grid cell from where they are placed
+fn object_rect(object: &ObjectStruct) -> Rectangle {
+    Rectangle::new(object.x as f32, object.y as f32, 40.0, 40.0)
+}
+
+// Selects objects in a box that was dragged out in the level
+// If the box is tiny it was just a click so only the object under the mouse gets picked
+// This checks the whole object instead of the grid cell so objects moved off grid with IJKL can still be picked
+pub fn select_objects(
+    object_grid: &mut [ObjectStruct],
+    object_columns: &ObjectColumns,
+    start: Vector2,
+    end: Vector2,
+    mode: SelectMode
+) {
+    let area = Rectangle::new(
+        start.x.min(end.x),
+        start.y.min(end.y),
+        (start.x - end.x).abs(),
+        (start.y - end.y).abs()
+    );
+
+    // Only objects near the box get checked
+    let in_area: Vec<usize> = if area.width < 5.0 && area.height < 5.0 {
+        // Objects placed later are drawn on top so those get picked first
+        object_columns.near_point(end.x as i32)
+            .into_iter()
+            .rev()
+            .find(|index| object_rect(&object_grid[*index]).check_collision_point_rec(end))
+            .into_iter()
+            .collect()
+    } else {
+        object_columns.in_range(area.x as i32 - 40, (area.x + area.width) as i32)
+            .into_iter()
+            .filter(|index| object_rect(&object_grid[*index]).check_collision_recs(&area))
+            .collect()
+    };
+
+    if mode == SelectMode::Replace {
+        for object in object_grid.iter_mut() {
+            object.selected = false;
         }
     }
+
+    for index in in_area {
+        object_grid[index].selected = mode != SelectMode::Subtract;
+    }
+}
+
+// Makes the buttons for the selected objects show what the first one is set to
+pub fn update_selection_buttons(
+    object_grid: &[ObjectStruct],
+    no_touch_toggle: &mut Button,
+    hide_toggle: &mut Button,
+    object_settings: &mut Button
+) {
+    match object_grid.iter().find(|object| object.selected) {
+        Some(object) => {
+            no_touch_toggle.is_disabled = object.no_touch != 1;
+            hide_toggle.is_disabled = object.hide != 1;
+            // Every object at least has a position and rotation to edit
+            object_settings.is_disabled = false;
+        }
+        None => {
+            no_touch_toggle.is_disabled = true;
+            hide_toggle.is_disabled = true;
+            object_settings.is_disabled = true;
+        }
+    }
+}
+
+// Snapped positions round towards 0 so negative ones are a cell off
+fn grid_cell(snapped: i32) -> i32 {
+    if snapped < 0 { snapped - 40 } else { snapped }
+}
+
+// Copies the selected objects with their properties
+// Positions are stored from the top left of the selection so they paste in the same shape
+pub fn copy_selected(object_grid: &[ObjectStruct]) -> Vec<ObjectStruct> {
+    let selected: Vec<&ObjectStruct> = object_grid.iter().filter(|object| object.selected).collect();
+    let left = selected.iter().map(|object| object.x).min().unwrap_or(0);
+    let top = selected.iter().map(|object| object.y).min().unwrap_or(0);
+
+    selected.into_iter()
+        .map(|object| ObjectStruct {
+            x: object.x - left,
+            y: object.y - top,
+            selected: false,
+            ..object.clone()
+        })
+        .collect()
+}
+
+// Puts copied objects into the level with the top left of them at x and y
+// The new objects end up being the only ones selected so they can be moved right away
+fn paste_objects(
+    object_grid: &mut Vec<ObjectStruct>,
+    history: &mut EditHistory,
+    clipboard: &[ObjectStruct],
+    x: i32,
+    y: i32,
+    time: f64
+) {
+    if clipboard.is_empty() {
+        return;
+    }
+
+    for object in object_grid.iter_mut() {
+        object.selected = false;
+    }
+
+    let pasted: Vec<ObjectStruct> = clipboard.iter()
+        .map(|object| ObjectStruct {
+            x: object.x + x,
+            y: object.y + y,
+            selected: true,
+            ..object.clone()
+        })
+        .collect();
+
+    history.add(object_grid, pasted, time);
+}
+
+// Copies go right on top of the objects they came from and don't touch the clipboard
+fn duplicate_selected(object_grid: &mut Vec<ObjectStruct>, history: &mut EditHistory, time: f64) {
+    let selected = object_grid.iter().filter(|object| object.selected);
+    let left = selected.clone().map(|object| object.x).min();
+    let top = selected.map(|object| object.y).min();
+
+    if let (Some(left), Some(top)) = (left, top) {
+        let copies = copy_selected(object_grid);
+        paste_objects(object_grid, history, &copies, left, top, time);
+    }
 }
 
 pub fn keybinds_manager(
     object_grid: &mut Vec<ObjectStruct>,
+    history: &mut EditHistory,
+    clipboard: &mut Vec<ObjectStruct>,
+    snapped_x: i32,
+    snapped_y: i32,
     rl: &RaylibHandle,
     start_pos: &mut u16
 ) {
+    let time = rl.get_time();
+    let ctrl_down = rl.is_key_down(KeyboardKey::KEY_LEFT_CONTROL) || rl.is_key_down(KeyboardKey::KEY_RIGHT_CONTROL);
+
     if rl.is_key_pressed(KeyboardKey::KEY_DELETE) {
-        let mut obj_index = 0;
-        while obj_index < object_grid.len() {
-            if object_grid[obj_index].selected {
-                object_grid.remove(obj_index);
-            } else {
-                obj_index += 1;
-            }
-        }
+        history.delete_where(object_grid, time, |object| object.selected);
     }
 
-    if rl.is_key_pressed(KeyboardKey::KEY_A) {
-        let mut obj_index = 0;
-        while obj_index < object_grid.len() {
-            if object_grid[obj_index].selected {
-                object_grid[obj_index].x -= 40;
-                obj_index += 1;
-            } else {
-                obj_index += 1;
+    // Ctrl+C copies, Ctrl+V pastes where the mouse is and Ctrl+D duplicates the selection in place
+    // Ctrl+A selects everything
+    if ctrl_down {
+        if rl.is_key_pressed(KeyboardKey::KEY_A) {
+            for object in object_grid.iter_mut() {
+                object.selected = true;
             }
         }
-    }
 
-    if rl.is_key_pressed(KeyboardKey::KEY_D) {
-        let mut obj_index = 0;
-        while obj_index < object_grid.len() {
-            if object_grid[obj_index].selected {
-                object_grid[obj_index].x += 40;
-                obj_index += 1;
-            } else {
-                obj_index += 1;
-            }
+        if rl.is_key_pressed(KeyboardKey::KEY_C) && object_grid.iter().any(|object| object.selected) {
+            *clipboard = copy_selected(object_grid);
         }
-    }
 
-    if rl.is_key_pressed(KeyboardKey::KEY_W) {
-        let mut obj_index = 0;
-        while obj_index < object_grid.len() {
-            if object_grid[obj_index].selected {
-                object_grid[obj_index].y -= 40;
-                obj_index += 1;
-            } else {
-                obj_index += 1;
-            }
+        if rl.is_key_pressed(KeyboardKey::KEY_V) {
+            paste_objects(object_grid, history, clipboard, grid_cell(snapped_x), grid_cell(snapped_y), time);
         }
-    }
 
-    if rl.is_key_pressed(KeyboardKey::KEY_S) {
-        let mut obj_index = 0;
-        while obj_index < object_grid.len() {
-            if object_grid[obj_index].selected {
-                object_grid[obj_index].y += 40;
-                obj_index += 1;
-            } else {
-                obj_index += 1;
-            }
+        if rl.is_key_pressed(KeyboardKey::KEY_D) {
+            duplicate_selected(object_grid, history, time);
         }
     }
 
-    if rl.is_key_pressed(KeyboardKey::KEY_J) {
-        let mut obj_index = 0;
-        while obj_index < object_grid.len() {
-            if object_grid[obj_index].selected {
-                object_grid[obj_index].x -= 1;
-                obj_index += 1;
-            } else {
-                obj_index += 1;
-            }
-        }
-    }
+    // Every move and rotate is saved in the history so it can be undone
+    // Moving the same objects a few times in a row gets undone all at once
+    let moves = [
+        (KeyboardKey::KEY_A, -40, 0),
+        (KeyboardKey::KEY_D, 40, 0),
+        (KeyboardKey::KEY_W, 0, -40),
+        (KeyboardKey::KEY_S, 0, 40),
+        (KeyboardKey::KEY_J, -1, 0),
+        (KeyboardKey::KEY_L, 1, 0),
+        (KeyboardKey::KEY_I, 0, -1),
+        (KeyboardKey::KEY_K, 0, 1)
+    ];
 
-    if rl.is_key_pressed(KeyboardKey::KEY_L) {
-        let mut obj_index = 0;
-        while obj_index < object_grid.len() {
-            if object_grid[obj_index].selected {
-                object_grid[obj_index].x += 1;
-                obj_index += 1;
-            } else {
-                obj_index += 1;
-            }
+    // Holding ctrl is for shortcuts like Ctrl+D so nothing moves
+    for (key, move_x, move_y) in moves {
+        if rl.is_key_pressed(key) && !ctrl_down {
+            history.change_selected(object_grid, EditKind::Move, time, |object| {
+                object.x += move_x;
+                object.y += move_y;
+            });
         }
     }
 
-    if rl.is_key_pressed(KeyboardKey::KEY_I) {
-        let mut obj_index = 0;
-        while obj_index < object_grid.len() {
-            if object_grid[obj_index].selected {
-                object_grid[obj_index].y -= 1;
-                obj_index += 1;
+    if rl.is_key_pressed(KeyboardKey::KEY_Q) && !ctrl_down {
+        history.change_selected(object_grid, EditKind::Rotate, time, |object| {
+            if object.rotation != -270 {
+                object.rotation -= 90;
             } else {
-                obj_index += 1;
+                object.rotation = 0;
             }
-        }
-    }
-
-    if rl.is_key_pressed(KeyboardKey::KEY_K) {
-        let mut obj_index = 0;
-        while obj_index < object_grid.len() {
-            if object_grid[obj_index].selected {
-                object_grid[obj_index].y += 1;
-                obj_index += 1;
-            } else {
-                obj_index += 1;
-            }
-        }
+        });
     }
 
-    if rl.is_key_pressed(KeyboardKey::KEY_Q) {
-        let mut obj_index = 0;
-        while obj_index < object_grid.len() {
-            if object_grid[obj_index].selected {
-                if object_grid[obj_index].rotation != -270 {
-                    object_grid[obj_index].rotation -= 90;
-                } else {
-                    object_grid[obj_index].rotation = 0;
-                }
-
-                obj_index += 1;
+    if rl.is_key_pressed(KeyboardKey::KEY_E) && !ctrl_down {
+        history.change_selected(object_grid, EditKind::Rotate, time, |object| {
+            if object.rotation != 270 {
+                object.rotation += 90;
             } else {
-                obj_index += 1;
+                object.rotation = 0;
             }
-        }
+        });
     }
 
-    if rl.is_key_pressed(KeyboardKey::KEY_E) {
-        let mut obj_index = 0;
-        while obj_index < object_grid.len() {
-            if object_grid[obj_index].selected {
-                if object_grid[obj_index].rotation != 270 {
-                    object_grid[obj_index].rotation += 90;
-                } else {
-                    object_grid[obj_index].rotation = 0;
-                }
+    // Ctrl+Z undoes and Ctrl+Y or Ctrl+Shift+Z redoes
+    if ctrl_down {
+        let shift_down = rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT);
 
-                obj_index += 1;
-            } else {
-                obj_index += 1;
-            }
+        if rl.is_key_pressed(KeyboardKey::KEY_Y)
+        || (rl.is_key_pressed(KeyboardKey::KEY_Z) && shift_down) {
+            history.redo(object_grid);
+        } else if rl.is_key_pressed(KeyboardKey::KEY_Z) {
+            history.undo(object_grid);
         }
     }
 
@@ -250,4 +287,126 @@ pub fn keybinds_manager(
             *start_pos -= 5;
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(x: i32, y: i32, id: u32, selected: bool) -> ObjectStruct {
+        ObjectStruct {
+            y,
+            x,
+            rotation: 0,
+            no_touch: 0,
+            hide: 0,
+            color_channel: 0,
+            id,
+            selected,
+            properties: None
+        }
+    }
+
+    fn positions(objects: &[ObjectStruct]) -> Vec<(i32, i32, u32)> {
+        objects.iter().map(|object| (object.x, object.y, object.id)).collect()
+    }
+
+    fn selected(object_grid: &[ObjectStruct]) -> Vec<usize> {
+        object_grid.iter().enumerate().filter(|(_, object)| object.selected).map(|(index, _)| index).collect()
+    }
+
+    #[test]
+    fn copying_keeps_the_shape_of_the_selection() {
+        let mut trigger = object(200, 440, 23, true);
+        trigger.properties = Some(vec!["255".to_string(); 7]);
+        let object_grid = vec![object(120, 480, 1, true), object(0, 0, 2, false), trigger.clone()];
+
+        let clipboard = copy_selected(&object_grid);
+
+        assert_eq!(positions(&clipboard), vec![(0, 40, 1), (80, 0, 23)]);
+        assert_eq!(clipboard[1].properties, trigger.properties);
+        assert!(clipboard.iter().all(|object| !object.selected));
+        assert!(copy_selected(&[object(0, 0, 1, false)]).is_empty());
+    }
+
+    #[test]
+    fn pasting_puts_the_top_left_at_the_cursor() {
+        let mut object_grid = vec![object(120, 480, 1, true)];
+        let mut history = EditHistory::new();
+        let clipboard = vec![object(0, 40, 1, false), object(80, 0, 2, false)];
+
+        paste_objects(&mut object_grid, &mut history, &clipboard, grid_cell(400), grid_cell(0), 0.0);
+        assert_eq!(positions(&object_grid), vec![(120, 480, 1), (400, 40, 1), (480, 0, 2)]);
+        assert_eq!(selected(&object_grid), vec![1, 2]);
+
+        // Snapped positions left of 0 are a cell off
+        paste_objects(&mut object_grid, &mut history, &clipboard, grid_cell(-40), grid_cell(80), 1.0);
+        assert_eq!(positions(&object_grid[3..]), vec![(-80, 120, 1), (0, 80, 2)]);
+
+        history.undo(&mut object_grid);
+        assert_eq!(object_grid.len(), 3);
+    }
+
+    #[test]
+    fn pasting_nothing_keeps_the_selection() {
+        let mut object_grid = vec![object(120, 480, 1, true)];
+
+        paste_objects(&mut object_grid, &mut EditHistory::new(), &[], 0, 0, 0.0);
+        assert_eq!(selected(&object_grid), vec![0]);
+    }
+
+    #[test]
+    fn duplicates_go_on_top_of_the_originals() {
+        let mut object_grid = vec![object(120, 480, 1, true), object(0, 0, 2, false), object(200, 440, 3, true)];
+        let mut history = EditHistory::new();
+
+        duplicate_selected(&mut object_grid, &mut history, 0.0);
+        assert_eq!(positions(&object_grid[3..]), vec![(120, 480, 1), (200, 440, 3)]);
+        assert_eq!(selected(&object_grid), vec![3, 4]);
+
+        // Nothing selected means nothing to duplicate
+        let mut object_grid = vec![object(0, 0, 2, false)];
+        duplicate_selected(&mut object_grid, &mut history, 1.0);
+        assert_eq!(object_grid.len(), 1);
+    }
+
+    #[test]
+    fn box_select_modes() {
+        let level = || vec![object(0, 480, 1, false), object(40, 480, 1, true), object(400, 480, 1, true)];
+        let object_columns = ObjectColumns::new(&level());
+        let start = Vector2::new(-10.0, 470.0);
+        let end = Vector2::new(50.0, 500.0);
+
+        let mut object_grid = level();
+        select_objects(&mut object_grid, &object_columns, start, end, SelectMode::Replace);
+        assert_eq!(selected(&object_grid), vec![0, 1]);
+
+        let mut object_grid = level();
+        select_objects(&mut object_grid, &object_columns, start, end, SelectMode::Add);
+        assert_eq!(selected(&object_grid), vec![0, 1, 2]);
+
+        let mut object_grid = level();
+        select_objects(&mut object_grid, &object_columns, start, end, SelectMode::Subtract);
+        assert_eq!(selected(&object_grid), vec![2]);
+
+        // Dragging from the bottom right works the same
+        let mut object_grid = level();
+        select_objects(&mut object_grid, &object_columns, end, start, SelectMode::Replace);
+        assert_eq!(selected(&object_grid), vec![0, 1]);
+    }
+
+    #[test]
+    fn clicking_picks_the_top_object() {
+        let mut object_grid = vec![object(40, 480, 1, false), object(50, 480, 2, false), object(400, 480, 1, true)];
+        let object_columns = ObjectColumns::new(&object_grid);
+        let click = Vector2::new(60.0, 490.0);
+
+        select_objects(&mut object_grid, &object_columns, click, click, SelectMode::Replace);
+        assert_eq!(selected(&object_grid), vec![1]);
+
+        // Clicking empty space clears the selection
+        let empty = Vector2::new(300.0, 100.0);
+        select_objects(&mut object_grid, &object_columns, empty, empty, SelectMode::Replace);
+        assert_eq!(selected(&object_grid), Vec::<usize>::new());
+    }
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+// Logging in gives you a session token and that gets saved instead of your password
+// The server says "Logged in!" with the token on the next line
+// The server doesn't send tokens yet so without one you are only logged in until the game is closed
+// Auto login, uploading and rating send the token and if the server doesn't take it anymore you have to log in again
+
+pub const LOGGED_IN_RESPONSE: &str = "Logged in!";
+pub const TOKEN_REJECTED_RESPONSE: &str = "Invalid token!";
+
+// Tokens can't be longer than this so a weird response doesn't end up in the save file
+const MAX_TOKEN_LENGTH: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub user: String,
+    // None when the server didn't give us one
+    pub token: Option<String>
+}
+
+impl Session {
+    // Only I (Puppet) can rate levels at the moment
+    pub fn is_mod(&self) -> bool {
+        self.user == "Puppet"
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LoginResponse {
+    LoggedIn(Session),
+    // The token is too old or got logged out so the login page has to be shown again
+    TokenRejected,
+    Failed(String)
+}
+
+fn is_valid_token(token: &str) -> bool {
+    !token.is_empty()
+    && token.len() <= MAX_TOKEN_LENGTH
+    && token.chars().all(|character| character.is_ascii_alphanumeric() || character == '-' || character == '_')
+}
+
+// Works out what the server said to a login
+// Auto login already has a token so the server doesn't have to send a new one
+pub fn parse_login_response(user: &str, response: &str, current_token: Option<&str>) -> LoginResponse {
+    let mut lines = response.lines().map(str::trim);
+
+    match lines.next() {
+        Some(LOGGED_IN_RESPONSE) => {
+            let token = lines.next().filter(|token| is_valid_token(token)).or(current_token);
+            LoginResponse::LoggedIn(Session { user: user.to_string(), token: token.map(str::to_string) })
+        }
+        _ if is_token_rejected(response) => LoginResponse::TokenRejected,
+        _ => LoginResponse::Failed(response.trim().to_string())
+    }
+}
+
+pub fn is_token_rejected(response: &str) -> bool {
+    response.trim() == TOKEN_REJECTED_RESPONSE
+}
+
+pub fn is_server_down(response: &str) -> bool {
+    response.contains("error code: 1033")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logging_in_gives_a_token() {
+        assert_eq!(
+            parse_login_response("Puppet", "Logged in!\nabc-123_XYZ\n", None),
+            LoginResponse::LoggedIn(Session { user: "Puppet".to_string(), token: Some("abc-123_XYZ".to_string()) })
+        );
+
+        // Servers that don't give out tokens still log you in, just without a token
+        let no_token = LoginResponse::LoggedIn(Session { user: "Puppet".to_string(), token: None });
+        assert_eq!(parse_login_response("Puppet", "Logged in!", None), no_token);
+        assert_eq!(parse_login_response("Puppet", "Logged in!\nnot a token", None), no_token);
+        assert_eq!(parse_login_response("Puppet", "Wrong password!", None), LoginResponse::Failed("Wrong password!".to_string()));
+    }
+
+    #[test]
+    fn auto_login_keeps_its_token() {
+        assert_eq!(
+            parse_login_response("someone", "Logged in!", Some("old")),
+            LoginResponse::LoggedIn(Session { user: "someone".to_string(), token: Some("old".to_string()) })
+        );
+        assert_eq!(
+            parse_login_response("someone", "Logged in!\nnew", Some("old")),
+            LoginResponse::LoggedIn(Session { user: "someone".to_string(), token: Some("new".to_string()) })
+        );
+        assert_eq!(parse_login_response("someone", "Invalid token!\n", Some("old")), LoginResponse::TokenRejected);
+    }
+}
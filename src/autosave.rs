@@ -0,0 +1,135 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Saving while you play so a crash doesn't throw away everything since the game was opened
+// Your progress gets saved as soon as it changes since that doesn't happen often
+// The level you are editing changes all the time so it waits until you stop for a bit
+
+// How long after the last change to the level it gets saved
+pub const EDITOR_AUTOSAVE_DELAY: f64 = 2.0;
+// Stuff gets saved at least this often even if you never stop changing it
+pub const AUTOSAVE_INTERVAL: f64 = 30.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Autosave {
+    delay: f64,
+    interval: f64,
+    // When the first change since the last save happened
+    first_change: Option<f64>,
+    last_change: f64,
+    last_save: f64
+}
+
+impl Autosave {
+    pub fn new(delay: f64, interval: f64) -> Autosave {
+        Autosave {
+            delay,
+            interval,
+            first_change: None,
+            last_change: 0.0,
+            last_save: 0.0
+        }
+    }
+
+    pub fn changed(&mut self, time: f64) {
+        if self.first_change.is_none() {
+            self.first_change = Some(time);
+        }
+        self.last_change = time;
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.first_change.is_some()
+    }
+
+    // With nothing changed it still says to save once the interval is up
+    // That catches changes that nothing told it about so whatever is saving should check if anything is actually different
+    pub fn should_save(&self, time: f64) -> bool {
+        match self.first_change {
+            Some(first_change) => time - self.last_change >= self.delay || time - first_change >= self.interval,
+            None => time - self.last_save >= self.interval
+        }
+    }
+
+    pub fn saved(&mut self, time: f64) {
+        self.first_change = None;
+        self.last_save = time;
+    }
+}
+
+// Closing the game from a terminal or the computer shutting down should save like closing the window does
+// This gives back a flag that gets set when that happens so the game loop can stop
+pub fn quit_signal() -> Arc<AtomicBool> {
+    let quit = Arc::new(AtomicBool::new(false));
+    let quit_clone = Arc::clone(&quit);
+
+    tokio::spawn(async move {
+        wait_for_quit_signal().await;
+        quit_clone.store(true, Ordering::SeqCst);
+    });
+
+    quit
+}
+
+#[cfg(unix)]
+async fn wait_for_quit_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::terminate()) {
+        Ok(mut terminate) => {
+            tokio::select! {
+                _ = terminate.recv() => {}
+                _ = tokio::signal::ctrl_c() => {}
+            }
+        }
+        Err(error) => {
+            println!("Failed to listen for SIGTERM: {}", error);
+            let _ = tokio::signal::ctrl_c().await;
+        }
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_quit_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waits_until_changes_stop() {
+        let mut autosave = Autosave::new(2.0, 30.0);
+        assert!(!autosave.should_save(1.0));
+
+        autosave.changed(1.0);
+        assert!(autosave.is_pending());
+        assert!(!autosave.should_save(2.5));
+
+        autosave.changed(2.5);
+        assert!(!autosave.should_save(4.0));
+        assert!(autosave.should_save(4.5));
+
+        autosave.saved(4.5);
+        assert!(!autosave.is_pending());
+        assert!(!autosave.should_save(5.0));
+    }
+
+    #[test]
+    fn saves_on_the_interval() {
+        let mut autosave = Autosave::new(2.0, 30.0);
+
+        // Changing something every second never stops long enough
+        for second in 0..30 {
+            autosave.changed(second as f64);
+            assert!(!autosave.should_save(second as f64));
+        }
+        assert!(autosave.should_save(30.0));
+
+        // And with nothing changed it still checks every so often
+        autosave.saved(30.0);
+        assert!(!autosave.should_save(59.0));
+        assert!(autosave.should_save(60.0));
+    }
+}
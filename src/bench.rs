@@ -0,0 +1,138 @@
+use std::time::{Duration, Instant};
+
+use crate::objects::ObjectRegistry;
+use crate::physics::{self, PhysicsInput, PhysicsState};
+use crate::spatial::ObjectColumns;
+use crate::types::ObjectStruct;
+
+// Compares the object columns against going through every object like the game used to
+// Putting every object in one column is the same as not having columns at all so both sides run the same code
+
+const TICKS: usize = 3600;
+const FRAMES: usize = 3600;
+const CLICKS: usize = 10_000;
+
+// A long level with a bit of everything in it
+// Everything is up in the air so the player never dies and the whole level gets played
+fn make_level(object_count: usize) -> Vec<ObjectStruct> {
+    let ids = [1, 2, 3, 4, 7, 10, 16, 17, 21];
+
+    (0..object_count)
+        .map(|index| ObjectStruct {
+            y: 40 + (index % 5) as i32 * 40,
+            x: 400 + (index / 5) as i32 * 40,
+            rotation: 0,
+            no_touch: 0,
+            hide: 0,
+            color_channel: 0,
+            id: ids[index % ids.len()],
+            selected: false,
+            properties: None
+        })
+        .collect()
+}
+
+fn time(mut run: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    run();
+    start.elapsed()
+}
+
+fn print_result(name: &str, every_object: Duration, columns: Duration) {
+    println!(
+        "{:<22} every object: {:>9.2}ms   columns: {:>9.2}ms   {:.1}x faster",
+        name,
+        every_object.as_secs_f64() * 1000.0,
+        columns.as_secs_f64() * 1000.0,
+        every_object.as_secs_f64() / columns.as_secs_f64().max(f64::EPSILON)
+    );
+}
+
+// This is what runs when you do geometry-rays bench [objects]
+// It gives back the exit code
+pub fn run(args: &[String]) -> i32 {
+    let object_count = match args.first().map(|count| count.parse::<usize>()) {
+        None => 20_000,
+        Some(Ok(count)) => count,
+        Some(Err(_)) => {
+            println!("Usage: geometry-rays bench [objects]");
+            return 2
+        }
+    };
+
+    let registry = ObjectRegistry::new();
+    let level = make_level(object_count);
+    let level_end = level.iter().map(|object| object.x).max().unwrap_or(0);
+
+    let mut columns = ObjectColumns::new(&[]);
+    let build_time = time(|| columns = ObjectColumns::new(&level));
+    let one_column = ObjectColumns::with_column_width(&level, i32::MAX);
+
+    println!("{} objects, level is {} long", object_count, level_end);
+    println!("Building the columns took {:.2}ms", build_time.as_secs_f64() * 1000.0);
+
+    // Physics for a minute of gameplay
+    let play = |object_columns: &ObjectColumns| {
+        let mut state = PhysicsState::new();
+        let jump = PhysicsInput { jump: true, mouse: true, ..Default::default() };
+        let let_go = PhysicsInput { released: true, ..Default::default() };
+
+        for tick in 0..TICKS {
+            physics::step(&mut state, if tick % 40 == 0 { jump } else { let_go }, &level, object_columns, "1", &registry);
+        }
+
+        state
+    };
+    let mut every_object_state = PhysicsState::new();
+    let mut columns_state = PhysicsState::new();
+    let every_object_time = time(|| every_object_state = play(&one_column));
+    let columns_time = time(|| columns_state = play(&columns));
+    print_result(&format!("{} physics ticks", TICKS), every_object_time, columns_time);
+
+    if every_object_state != columns_state {
+        println!("The physics came out different with columns!");
+        return 1
+    }
+
+    // Finding what to draw while scrolling through the level
+    let scroll_through = |object_columns: &ObjectColumns| {
+        let mut drawn = 0;
+        for frame in 0..FRAMES {
+            let camera_x = (frame * 6) as i32 % level_end.max(1);
+            drawn += object_columns.in_range(camera_x - 40, camera_x + 800).len();
+        }
+        drawn
+    };
+    let mut every_object_drawn = 0;
+    let mut columns_drawn = 0;
+    let every_object_time = time(|| every_object_drawn = scroll_through(&one_column));
+    let columns_time = time(|| columns_drawn = scroll_through(&columns));
+    print_result(&format!("{} frames drawn", FRAMES), every_object_time, columns_time);
+
+    if every_object_drawn != columns_drawn {
+        println!("Different objects got drawn with columns!");
+        return 1
+    }
+
+    // Clicking on objects in the editor
+    let click_around = |object_columns: &ObjectColumns| {
+        let mut found = 0;
+        for click in 0..CLICKS {
+            let x = (click * 97) as i32 % level_end.max(1);
+            found += object_columns.near_point(x).len();
+        }
+        found
+    };
+    let mut every_object_found = 0;
+    let mut columns_found = 0;
+    let every_object_time = time(|| every_object_found = click_around(&one_column));
+    let columns_time = time(|| columns_found = click_around(&columns));
+    print_result(&format!("{} editor clicks", CLICKS), every_object_time, columns_time);
+
+    if every_object_found != columns_found {
+        println!("Different objects got clicked with columns!");
+        return 1
+    }
+
+    0
+}
@@ -0,0 +1,177 @@
+use std::fs;
+
+use crate::colors::{ColorTarget, MAX_COLOR_CHANNEL};
+use crate::migrations::upgrade_level;
+use crate::objects::{Behavior, ObjectRegistry};
+use crate::types::Level;
+
+// Checks a level for stuff that would break it in game
+// Gives back a message for every problem it finds
+pub fn check_level(level_data: &str, registry: &ObjectRegistry) -> Vec<String> {
+    // Older levels get checked the way the game loads them
+    let level = match Level::parse(level_data) {
+        Ok(level) => upgrade_level(level),
+        Err(error) => return vec![error.to_string()]
+    };
+
+    let mut problems: Vec<String> = vec![];
+
+    for (index, object) in level.objects.iter().enumerate() {
+        if !registry.exists(object.id) {
+            problems.push(format!("Object {} has id {} which isnt an object", index, object.id));
+        }
+
+        if object.color_channel > MAX_COLOR_CHANNEL {
+            problems.push(format!(
+                "Object {} has color channel {} but it has to be from 1 to {} or 0 for none",
+                index,
+                object.color_channel,
+                MAX_COLOR_CHANNEL
+            ));
+        }
+
+        // The parser already makes sure the colors are from 0 to 255 and the fade time is a number
+        // So the only things left to check are what it changes and the easing
+        if registry.get(object.id).is_some_and(|object_type| object_type.behavior == Behavior::ColorTrigger) {
+            if let Some(properties) = &object.properties {
+                if properties[3] != "1" && properties[3] != "2" && properties[3] != "3" {
+                    problems.push(format!(
+                        "Object {} is a color trigger with type {} but it has to be 1 (bg), 2 (ground) or 3 (channel)",
+                        index,
+                        properties[3]
+                    ));
+                } else if properties[3] == "3"
+                && ColorTarget::from_trigger(3, properties.get(6).map(|channel| channel.as_str()).unwrap_or("")).is_none() {
+                    problems.push(format!(
+                        "Object {} is a color trigger for a channel but its target channel has to be from 1 to {}",
+                        index,
+                        MAX_COLOR_CHANNEL
+                    ));
+                }
+
+                if properties.get(5).is_some_and(|easing| easing != "0" && easing != "1") {
+                    problems.push(format!(
+                        "Object {} is a color trigger with easing {} but it has to be 0 (linear) or 1 (ease)",
+                        index,
+                        properties[5]
+                    ));
+                }
+            }
+        }
+    }
+
+    if !level.objects.iter().any(|object| {
+        registry.get(object.id).is_some_and(|object_type| object_type.behavior == Behavior::EndTrigger)
+    }) {
+        problems.push("Level has no end trigger (id 15)".to_string());
+    }
+
+    problems
+}
+
+// This is what runs when you do geometry-rays check <file>...
+// It gives back the exit code
+pub fn run(paths: &[String]) -> i32 {
+    if paths.is_empty() {
+        println!("Usage: geometry-rays check <file>...");
+        return 2
+    }
+
+    // Levels can use custom objects so those count as real objects too
+    let registry = ObjectRegistry::with_mods();
+
+    let mut found_problems = false;
+    for path in paths {
+        let problems = match fs::read_to_string(path) {
+            Ok(level_data) => check_level(&level_data, &registry),
+            Err(error) => vec![format!("Failed to read file: {}", error)]
+        };
+
+        if problems.is_empty() {
+            println!("{}: ok", path);
+        } else {
+            found_problems = true;
+
+            for problem in problems {
+                println!("{}: {}", path, problem);
+            }
+        }
+    }
+
+    if found_problems { 1 } else { 0 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::LevelParseError;
+
+    // A spike and an end trigger with whatever objects get added to it
+    fn check(objects: &str) -> Vec<String> {
+        check_level(
+            &format!("version:1.7;mode:1;;;480:480:0:0:0:1:0;480:2000:0:0:0:15:0{}", objects),
+            &ObjectRegistry::new()
+        )
+    }
+
+    #[test]
+    fn clean_level_has_no_problems() {
+        assert_eq!(check(""), Vec::<String>::new());
+        assert_eq!(check(";480:600:0:0:0:23:0:255:0:0:3:0.5:1:20"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn missing_end_trigger() {
+        assert_eq!(
+            check_level("version:1.7;mode:1;;;480:480:0:0:0:1:0", &ObjectRegistry::new()),
+            vec!["Level has no end trigger (id 15)".to_string()]
+        );
+    }
+
+    #[test]
+    fn unknown_id() {
+        assert_eq!(check(";480:600:0:0:0:9999:0"), vec!["Object 2 has id 9999 which isnt an object".to_string()]);
+    }
+
+    #[test]
+    fn bad_color_trigger_type_and_easing() {
+        assert_eq!(
+            check(";480:600:0:0:0:23:0:255:0:0:4:0:2:1"),
+            vec![
+                "Object 2 is a color trigger with type 4 but it has to be 1 (bg), 2 (ground) or 3 (channel)".to_string(),
+                "Object 2 is a color trigger with easing 2 but it has to be 0 (linear) or 1 (ease)".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_channels() {
+        assert_eq!(
+            check(";480:600:0:0:0:23:0:255:0:0:3:0:0:1000"),
+            vec!["Object 2 is a color trigger for a channel but its target channel has to be from 1 to 999".to_string()]
+        );
+        assert_eq!(
+            check(";480:600:0:0:0:2:1000"),
+            vec!["Object 2 has color channel 1000 but it has to be from 1 to 999 or 0 for none".to_string()]
+        );
+        assert_eq!(check(";480:600:0:0:0:2:0"), Vec::<String>::new());
+
+        // 1.6 levels had the channel after the properties
+        assert_eq!(
+            check_level("version:1.6;mode:1;;;480:2000:0:0:0:15;480:600:0:0:0:23:255:0:0:1:0:0:1:1000", &ObjectRegistry::new()),
+            vec!["Object 1 has color channel 1000 but it has to be from 1 to 999 or 0 for none".to_string()]
+        );
+    }
+
+    #[test]
+    fn parse_errors_get_passed_through() {
+        assert_eq!(
+            check_level("version:1.7;mode:1", &ObjectRegistry::new()),
+            vec![LevelParseError::MissingObjectSection.to_string()]
+        );
+        assert_eq!(
+            check(";480:x:0:0:0:2:0"),
+            vec!["Object 2 at character 63: x 'x' is not a valid number".to_string()]
+        );
+    }
+}
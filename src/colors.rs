@@ -0,0 +1,196 @@
+use std::collections::BTreeMap;
+
+use crate::physics::TICK_TIME;
+
+// Color triggers can fade to their color instead of changing it instantly
+// Fades move forward once every physics tick so they take the same time on any frame rate and in replays
+
+pub type Rgb = (u8, u8, u8);
+
+// Levels can have their own color channels from 1 to 999 that objects get tinted with
+// They are saved in the level as c1:255,0,0 next to c1001 and c1002
+pub type ColorChannels = BTreeMap<u16, Rgb>;
+pub const MAX_COLOR_CHANNEL: u16 = 999;
+
+// Channels that aren't in the level are white like objects are by default
+pub const DEFAULT_CHANNEL_COLOR: Rgb = (255, 255, 255);
+
+// What a color trigger changes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorTarget {
+    Background,
+    Ground,
+    Channel(u16)
+}
+
+impl ColorTarget {
+    // The type is 1 for the bg, 2 for the ground and 3 for one of the level's channels
+    // Anything else doesn't change anything
+    pub fn from_trigger(color_type: u8, channel: &str) -> Option<ColorTarget> {
+        match color_type {
+            1 => Some(ColorTarget::Background),
+            2 => Some(ColorTarget::Ground),
+            3 => channel.parse()
+                .ok()
+                .filter(|channel| (1..=MAX_COLOR_CHANNEL).contains(channel))
+                .map(ColorTarget::Channel),
+            _ => None
+        }
+    }
+}
+
+// Turns c1 to c999 into the channel number
+// Anything written differently like c01 isn't a channel so it gets saved back exactly how it was
+pub fn channel_key(key: &str) -> Option<u16> {
+    let number = key.strip_prefix('c')?;
+    let channel: u16 = number.parse().ok()?;
+
+    if (1..=MAX_COLOR_CHANNEL).contains(&channel) && channel.to_string() == number {
+        Some(channel)
+    } else {
+        None
+    }
+}
+
+// How a fade speeds up and slows down
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Easing {
+    Linear,
+    // Starts slow, speeds up in the middle and slows down at the end
+    Ease
+}
+
+impl Easing {
+    // Triggers from before fades existed don't have an easing so they are linear
+    pub fn from_property(value: &str) -> Easing {
+        match value {
+            "1" => Easing::Ease,
+            _ => Easing::Linear
+        }
+    }
+
+    // Takes how far along the fade is in time from 0 to 1 and gives back how far along the color is
+    pub fn apply(&self, progress: f32) -> f32 {
+        let progress = progress.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => progress,
+            Easing::Ease => progress * progress * (3.0 - 2.0 * progress)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorTransition {
+    pub target: ColorTarget,
+    from: Rgb,
+    to: Rgb,
+    // In seconds
+    duration: f32,
+    elapsed: f32,
+    easing: Easing
+}
+
+impl ColorTransition {
+    pub fn new(target: ColorTarget, from: Rgb, to: Rgb, duration: f32, easing: Easing) -> ColorTransition {
+        ColorTransition {
+            target,
+            from,
+            to,
+            duration: duration.max(0.0),
+            elapsed: 0.0,
+            easing
+        }
+    }
+
+    // Moves the fade forward one tick and gives back the color it's at now
+    // A fade with no duration goes straight to its color
+    pub fn tick(&mut self) -> Rgb {
+        self.elapsed += TICK_TIME;
+
+        if self.is_finished() {
+            return self.to;
+        }
+
+        let amount = self.easing.apply(self.elapsed / self.duration);
+        let mix = |from: u8, to: u8| (from as f32 + (to as f32 - from as f32) * amount).round() as u8;
+
+        (mix(self.from.0, self.to.0), mix(self.from.1, self.to.1), mix(self.from.2, self.to.2))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+// Starts a fade and stops any fade that was already changing the same thing
+// The new fade starts from wherever the old one got to
+pub fn start_transition(transitions: &mut Vec<ColorTransition>, transition: ColorTransition) {
+    transitions.retain(|other| other.target != transition.target);
+    transitions.push(transition);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fades_take_their_duration() {
+        let mut transition = ColorTransition::new(ColorTarget::Background, (0, 0, 0), (200, 100, 50), 1.0, Easing::Linear);
+
+        for _ in 0..30 {
+            transition.tick();
+        }
+        assert_eq!(transition.tick(), (103, 52, 26));
+        assert!(!transition.is_finished());
+
+        for _ in 0..30 {
+            transition.tick();
+        }
+        assert!(transition.is_finished());
+        assert_eq!(transition.tick(), (200, 100, 50));
+    }
+
+    #[test]
+    fn no_duration_is_instant() {
+        let mut transition = ColorTransition::new(ColorTarget::Ground, (0, 0, 0), (255, 0, 0), 0.0, Easing::Ease);
+
+        assert_eq!(transition.tick(), (255, 0, 0));
+        assert!(transition.is_finished());
+    }
+
+    #[test]
+    fn ease_is_slower_at_the_ends() {
+        assert_eq!(Easing::Ease.apply(0.5), 0.5);
+        assert!(Easing::Ease.apply(0.1) < Easing::Linear.apply(0.1));
+        assert!(Easing::Ease.apply(0.9) > Easing::Linear.apply(0.9));
+        assert_eq!(Easing::from_property("7"), Easing::Linear);
+    }
+
+    #[test]
+    fn new_fades_replace_old_ones() {
+        let mut transitions = vec![];
+
+        start_transition(&mut transitions, ColorTransition::new(ColorTarget::Background, (0, 0, 0), (255, 0, 0), 2.0, Easing::Linear));
+        start_transition(&mut transitions, ColorTransition::new(ColorTarget::Channel(5), (0, 0, 0), (0, 255, 0), 2.0, Easing::Linear));
+        start_transition(&mut transitions, ColorTransition::new(ColorTarget::Channel(6), (0, 0, 0), (0, 255, 0), 2.0, Easing::Linear));
+        start_transition(&mut transitions, ColorTransition::new(ColorTarget::Background, (0, 0, 0), (0, 0, 255), 2.0, Easing::Linear));
+
+        assert_eq!(transitions.len(), 3);
+        assert_eq!(transitions[2].to, (0, 0, 255));
+    }
+
+    #[test]
+    fn triggers_can_target_channels() {
+        assert_eq!(ColorTarget::from_trigger(1, ""), Some(ColorTarget::Background));
+        assert_eq!(ColorTarget::from_trigger(3, "12"), Some(ColorTarget::Channel(12)));
+        assert_eq!(ColorTarget::from_trigger(3, "1000"), None);
+        assert_eq!(ColorTarget::from_trigger(3, ""), None);
+        assert_eq!(ColorTarget::from_trigger(4, "12"), None);
+
+        assert_eq!(channel_key("c999"), Some(999));
+        assert_eq!(channel_key("c1001"), None);
+        assert_eq!(channel_key("c01"), None);
+        assert_eq!(channel_key("c0"), None);
+    }
+}
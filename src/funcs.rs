@@ -1,5 +1,14 @@
 use raylib::prelude::*;
 use crate::types::*;
+use crate::migrations::{LEVEL_VERSIONS, CURRENT_LEVEL_VERSION, upgrade_level};
+use crate::objects::{ObjectCategory, ObjectRegistry};
+use crate::inspector::{FieldTarget, InspectorField};
+use crate::colors::{channel_key, ColorChannels, DEFAULT_CHANNEL_COLOR, MAX_COLOR_CHANNEL, Rgb};
+use crate::player::{gamemode_index, PlayerLook};
+use crate::level_info::{INFO_KEYS, LevelInfo};
+use rodio::Decoder;
+use std::fs::File;
+use std::io::BufReader;
 
 impl Button {
     pub fn new(x: f32, y: f32, width: f32, height: f32, text: &str, font_size: i32, is_disabled: bool) -> Self {
@@ -279,6 +288,14 @@ impl TextBox {
                 text.push(' ');
             }
 
+            else if rl.is_key_pressed(KeyboardKey::KEY_MINUS) && self.symbols_allowed {
+                text.push('-');
+            }
+
+            else if rl.is_key_pressed(KeyboardKey::KEY_PERIOD) && self.symbols_allowed {
+                text.push('.');
+            }
+
             else if rl.is_key_pressed(KeyboardKey::KEY_ONE) {
                 text.push('1');
             }
@@ -341,62 +358,619 @@ macro_rules! hashmap {
     }
 }
 
-// Function used for converting a level into text
-pub fn get_level_text(current_mode: &str, current_song: u8, bg_red: u8, bg_green: u8, bg_blue: u8, ground_red: u8, ground_green: u8, ground_blue: u8, object_grid: &Vec<ObjectStruct>) -> String {
-    let mut level_string = format!(
-        "version:1.6;mode:{};song:{};c1001:{},{},{};c1002:{},{},{};c1004:255,255,255;bg:1;grnd:1;;;",
-
-        current_mode,
-        current_song,
-
-        bg_red,
-        bg_green,
-        bg_blue,
-
-        ground_red,
-        ground_green,
-        ground_blue
-    ).to_string();
-
-    for object in object_grid {
-        if object.id == 23 {
-            level_string.push_str(&format!(
-                "{}:{}:{}:{}:{}:{}:{}:{}:{}:{};",
-                object.y,
-                object.x,
-                object.rotation,
-                object.no_touch,
-                object.hide,
-                object.id,
-                object.properties.clone().unwrap()[0],
-                object.properties.clone().unwrap()[1],
-                object.properties.clone().unwrap()[2],
-                object.properties.clone().unwrap()[3]
-            ));
+// The texture for an object
+// Textures are loaded in the same order as the registry and anything not in it gets the null texture
+pub fn object_texture<'a>(registry: &ObjectRegistry, object_textures: &'a [Texture2D], id: u32) -> &'a Texture2D {
+    &object_textures[registry.position(id).unwrap_or(0)]
+}
+
+// Draws an object centered on x and y so it rotates around its middle
+pub fn draw_object(
+    d: &mut RaylibDrawHandle,
+    registry: &ObjectRegistry,
+    object_textures: &[Texture2D],
+    object: &ObjectStruct,
+    x: f32,
+    y: f32,
+    tint: Color
+) {
+    let texture = object_texture(registry, object_textures, object.id);
+    let (scale, offset_x) = match registry.get(object.id) {
+        Some(object_type) => (object_type.draw_scale, object_type.draw_offset_x),
+        None => (0.05, 0.0)
+    };
+
+    d.draw_texture_pro(
+        texture,
+        Rectangle::new(0.0, 0.0, texture.width as f32, texture.height as f32),
+        Rectangle::new(
+            x + offset_x,
+            y,
+            texture.width as f32 * scale,
+            texture.height as f32 * scale
+        ),
+        Vector2::new(
+            texture.width as f32 / 2.0 * scale,
+            texture.height as f32 / 2.0 * scale
+        ),
+        object.rotation as f32,
+        tint
+    );
+}
+
+// The color an object gets drawn with
+// Objects with a color channel use it and everything else uses the normal object color
+pub fn object_tint(color_channels: &ColorChannels, object: &ObjectStruct, default: Color) -> Color {
+    match object.color_channel {
+        channel @ 1..=MAX_COLOR_CHANNEL => {
+            let (r, g, b) = color_channels.get(&channel).copied().unwrap_or(DEFAULT_CHANNEL_COLOR);
+            Color { r, g, b, a: 255 }
+        }
+        _ => default
+    }
+}
+
+pub fn rgb_color((r, g, b): Rgb) -> Color {
+    Color { r, g, b, a: 255 }
+}
+
+// Draws the player's icon for a gamemode with the middle of the rect where the player is
+// If the icon they picked isn't there anymore the first one gets used and if there are none it's just a square
+pub fn draw_player(
+    d: &mut RaylibDrawHandle,
+    player_icons: &[Vec<PlayerIcon>],
+    look: &PlayerLook,
+    gamemode: GameMode,
+    rect: Rectangle,
+    rotation: f32,
+    primary: Color
+) {
+    let icons = &player_icons[gamemode_index(gamemode)];
+    let icon = icons.iter()
+        .find(|icon| icon.icon == look.icon(gamemode))
+        .or(icons.first());
+
+    let icon = match icon {
+        Some(icon) => icon,
+        None => {
+            d.draw_rectangle_pro(rect, Vector2::new(rect.width / 2.0, rect.height / 2.0), rotation, primary);
+            return;
+        }
+    };
+
+    let layers = [(Some(&icon.texture), primary), (icon.secondary.as_ref(), rgb_color(look.secondary))];
+    for (texture, tint) in layers {
+        if let Some(texture) = texture {
+            d.draw_texture_pro(
+                texture,
+                Rectangle::new(0.0, 0.0, texture.width as f32, texture.height as f32),
+                rect,
+                Vector2::new(rect.width / 2.0, rect.height / 2.0),
+                rotation,
+                tint
+            );
+        }
+    }
+}
+
+// Where the icons and colors go on the customization screen
+pub fn icon_choice_rect(index: usize) -> Rectangle {
+    Rectangle::new(100.0 + 70.0 * (index % 9) as f32, 220.0 + 70.0 * (index / 9) as f32, 60.0, 60.0)
+}
+
+pub fn color_choice_rect(index: usize, y: f32) -> Rectangle {
+    Rectangle::new(100.0 + 38.0 * index as f32, y, 32.0, 32.0)
+}
+
+// How many levels fit on the level list at once
+pub const LIBRARY_ROWS: usize = 7;
+
+pub fn library_row_rect(row: usize) -> Rectangle {
+    Rectangle::new(20.0, 90.0 + 52.0 * row as f32, 760.0, 48.0)
+}
+
+// What goes in the red, green and blue boxes of the channel editor
+// Channels the level doesn't use start empty
+pub fn channel_editor_text(color_channels: &ColorChannels, channel: u16) -> Vec<String> {
+    match color_channels.get(&channel) {
+        Some((r, g, b)) => vec![r.to_string(), g.to_string(), b.to_string()],
+        None => vec![String::new(); 3]
+    }
+}
+
+// How the object buttons are laid out in the editor
+pub const OBJECT_BUTTON_COLUMNS: usize = 11;
+pub const OBJECT_BUTTON_ROWS: usize = 3;
+pub const OBJECT_BUTTONS_PER_PAGE: usize = OBJECT_BUTTON_COLUMNS * OBJECT_BUTTON_ROWS;
+
+// Makes a button for every object in a palette tab or that matches the search
+// The buttons fill up the palette row by row and go onto the next page when it's full
+pub fn object_buttons(registry: &ObjectRegistry, category: ObjectCategory, search: &str) -> Vec<ObjButton> {
+    let obj_button_off = 52.0;
+
+    registry.palette(category, search)
+        .into_iter()
+        .enumerate()
+        .map(|(index, obj_id)| ObjButton {
+            btn: Button::new(
+                187.0 + obj_button_off * (index % OBJECT_BUTTON_COLUMNS) as f32,
+                437.0 + obj_button_off * (index % OBJECT_BUTTONS_PER_PAGE / OBJECT_BUTTON_COLUMNS) as f32,
+                45.0,
+                45.0,
+                registry.name(obj_id),
+                10,
+                false
+            ),
+            obj_id,
+            page: index / OBJECT_BUTTONS_PER_PAGE
+        })
+        .collect()
+}
+
+// Lays out the object settings popup
+// The fields every object has go on the left and the ones for the kind of object go on the right
+pub fn inspector_rows(fields: Vec<InspectorField>) -> Vec<InspectorRow> {
+    let mut left_rows = 0;
+    let mut right_rows = 0;
+
+    fields.into_iter()
+        .map(|field| {
+            let (x, row) = if let FieldTarget::Property { .. } = field.target {
+                right_rows += 1;
+                (540.0, right_rows - 1)
+            } else {
+                left_rows += 1;
+                (240.0, left_rows - 1)
+            };
+            let y = 160.0 + 45.0 * row as f32;
+
+            InspectorRow {
+                textbox: TextBox {
+                    rect: Rectangle {
+                        x,
+                        y,
+                        width: 140.0,
+                        height: 35.0
+                    },
+                    text: field.label(),
+                    text_size: 20,
+                    max_length: 11,
+                    spaces_allowed: false,
+                    symbols_allowed: true,
+                    active: false
+                },
+                text: field.value.clone().unwrap_or_default(),
+                button: Button::new(x, y, 140.0, 35.0, &field.label(), 18, false),
+                field
+            }
+        })
+        .collect()
+}
+
+// Puts the new values into the popup after something changed without moving anything around
+pub fn refresh_inspector_rows(rows: &mut [InspectorRow], fields: Vec<InspectorField>) {
+    for (row, field) in rows.iter_mut().zip(fields) {
+        row.textbox.text = field.label();
+        row.button.text = field.label();
+
+        if !row.textbox.active {
+            row.text = field.value.clone().unwrap_or_default();
+        }
+
+        row.field = field;
+    }
+}
+
+// The order metadata keys get written in if the level doesnt have its own order
+const DEFAULT_KEY_ORDER: [&str; 8] = ["version", "mode", "song", "c1001", "c1002", "c1004", "bg", "grnd"];
+
+fn parse_color(value: &str) -> Result<(u8, u8, u8), String> {
+    let colors: Vec<&str> = value.split(',').collect();
+    if colors.len() != 3 {
+        return Err(format!("needs 3 colors but has {}", colors.len()))
+    }
+
+    let channel = |color: &str| color
+        .parse::<u8>()
+        .map_err(|_| format!("'{}' is not a color from 0 to 255", color));
+
+    Ok((channel(colors[0])?, channel(colors[1])?, channel(colors[2])?))
+}
+
+// Parses one field of an object and says exactly where it broke if it cant
+fn parse_object_field<T: std::str::FromStr>(
+    fields: &[&str],
+    field_index: usize,
+    field: &'static str,
+    object_index: usize,
+    object_offset: usize
+) -> Result<T, LevelParseError> {
+    // The offset of the field is the offset of the object plus every field before it and their colons
+    let offset = object_offset + fields[..field_index].iter().map(|field| field.len() + 1).sum::<usize>();
+
+    fields[field_index].parse().map_err(|_| LevelParseError::InvalidObject {
+        index: object_index,
+        offset,
+        field,
+        reason: if fields[field_index].is_empty() {
+            "is empty".to_string()
+        } else if fields[field_index].parse::<i64>().is_ok() {
+            format!("'{}' is out of range", fields[field_index])
         } else {
-            level_string.push_str(&format!(
-                "{}:{}:{}:{}:{}:{};",
-                object.y,
-                object.x,
-                object.rotation,
-                object.no_touch,
-                object.hide,
-                object.id
-            ));
+            format!("'{}' is not a valid number", fields[field_index])
         }
+    })
+}
+
+// ALPHA and BETA objects are y:x:rot:id
+// Everything newer is y:x:rot:nt:hide:id and then any properties the object has
+fn has_short_objects(version: &str) -> bool {
+    version == "ALPHA" || version == "BETA"
+}
+
+// 1.7 added the color channel right after the id so the properties start one later
+// Before that the channel was one of the properties
+fn has_channel_field(version: &str) -> bool {
+    !matches!(version, "ALPHA" | "BETA" | "1.3" | "1.4" | "1.5" | "1.6")
+}
+
+fn parse_object(object: &str, version: &str, index: usize, offset: usize) -> Result<ObjectStruct, LevelParseError> {
+    let fields: Vec<&str> = object.split(':').collect();
+    let field_count = if has_short_objects(version) {
+        4
+    } else if has_channel_field(version) {
+        7
+    } else {
+        6
+    };
+
+    if fields.len() < field_count || (has_short_objects(version) && fields.len() > field_count) {
+        return Err(LevelParseError::InvalidObject {
+            index,
+            offset,
+            field: "object",
+            reason: format!("needs {} fields but has {}", field_count, fields.len())
+        })
     }
 
-    if !object_grid.is_empty() {
-        level_string.pop();
+    if has_short_objects(version) {
+        return Ok(ObjectStruct {
+            y: parse_object_field(&fields, 0, "y", index, offset)?,
+            x: parse_object_field(&fields, 1, "x", index, offset)?,
+            rotation: parse_object_field(&fields, 2, "rotation", index, offset)?,
+            no_touch: 0,
+            hide: 0,
+            color_channel: 0,
+            id: parse_object_field(&fields, 3, "id", index, offset)?,
+            selected: false,
+            properties: None
+        })
     }
 
-    level_string
+    let id: u32 = parse_object_field(&fields, 5, "id", index, offset)?;
+    let color_channel: u16 = if has_channel_field(version) {
+        parse_object_field(&fields, 6, "color channel", index, offset)?
+    } else {
+        0
+    };
+
+    // The properties are whatever is left after the fields every object has
+    let first = field_count;
+    let properties: Option<Vec<String>> = if fields.len() > first {
+        Some(fields[first..].iter().map(|property| property.to_string()).collect())
+    } else {
+        None
+    };
+
+    // Color triggers always need their red, green, blue and type
+    if id == 23 {
+        if fields.len() < first + 4 {
+            return Err(LevelParseError::InvalidObject {
+                index,
+                offset,
+                field: "properties",
+                reason: format!("need 4 for a color trigger but there are {}", fields.len() - first)
+            })
+        }
+
+        parse_object_field::<u8>(&fields, first, "red", index, offset)?;
+        parse_object_field::<u8>(&fields, first + 1, "green", index, offset)?;
+        parse_object_field::<u8>(&fields, first + 2, "blue", index, offset)?;
+        parse_object_field::<u8>(&fields, first + 3, "color type", index, offset)?;
+
+        // The fade time and easing were added later so older triggers don't have them
+        if fields.len() > first + 4 {
+            let fade_time: f32 = parse_object_field(&fields, first + 4, "fade time", index, offset)?;
+
+            if !fade_time.is_finite() || fade_time < 0.0 {
+                return Err(LevelParseError::InvalidObject {
+                    index,
+                    offset: offset + fields[..first + 4].iter().map(|field| field.len() + 1).sum::<usize>(),
+                    field: "fade time",
+                    reason: format!("'{}' has to be 0 or more seconds", fields[first + 4])
+                })
+            }
+        }
+
+        if fields.len() > first + 5 {
+            parse_object_field::<u8>(&fields, first + 5, "easing", index, offset)?;
+        }
+
+        if fields.len() > first + 6 {
+            parse_object_field::<u16>(&fields, first + 6, "target channel", index, offset)?;
+        }
+    }
+
+    Ok(ObjectStruct {
+        y: parse_object_field(&fields, 0, "y", index, offset)?,
+        x: parse_object_field(&fields, 1, "x", index, offset)?,
+        rotation: parse_object_field(&fields, 2, "rotation", index, offset)?,
+        no_touch: parse_object_field(&fields, 3, "no touch", index, offset)?,
+        hide: parse_object_field(&fields, 4, "hide", index, offset)?,
+        color_channel,
+        id,
+        selected: false,
+        properties
+    })
+}
+
+fn object_to_string(object: &ObjectStruct, version: &str) -> String {
+    if has_short_objects(version) {
+        return format!("{}:{}:{}:{}", object.y, object.x, object.rotation, object.id)
+    }
+
+    let mut object_string = format!(
+        "{}:{}:{}:{}:{}:{}",
+        object.y,
+        object.x,
+        object.rotation,
+        object.no_touch,
+        object.hide,
+        object.id
+    );
+
+    if has_channel_field(version) {
+        object_string.push_str(&format!(":{}", object.color_channel));
+    }
+
+    if let Some(properties) = &object.properties {
+        for property in properties {
+            object_string.push(':');
+            object_string.push_str(property);
+        }
+    }
+
+    object_string
+}
+
+impl Level {
+    // Turns a level string into a level
+    pub fn parse(level_data: &str) -> Result<Level, LevelParseError> {
+        Level::parse_counting_bytes(level_data).map_err(|error| error.counted_in_characters(level_data))
+    }
+
+    // The offsets in here are in bytes since that's what slicing the string uses
+    fn parse_counting_bytes(level_data: &str) -> Result<Level, LevelParseError> {
+        let (metadata, object_string) = level_data
+            .split_once(";;;")
+            .ok_or(LevelParseError::MissingObjectSection)?;
+
+        let mut level = Level {
+            version: "".to_string(),
+            mode: None,
+            song: None,
+            c1001: None,
+            c1002: None,
+            c1004: None,
+            channels: ColorChannels::new(),
+            bg: None,
+            grnd: None,
+            info: LevelInfo::default(),
+            extra: vec![],
+            key_order: vec![],
+            objects: vec![]
+        };
+
+        // This handles the level metadata
+        let mut offset = 0;
+        for pair in metadata.split(';') {
+            let invalid = |reason: String| LevelParseError::InvalidMetadata {
+                offset,
+                pair: pair.to_string(),
+                reason
+            };
+            let (key, value) = pair
+                .split_once(':')
+                .ok_or_else(|| invalid("is missing a ':'".to_string()))?;
+            let not_a_number = |_| invalid(format!("'{}' is not a valid number", value));
+
+            match key {
+                "version" => level.version = value.to_string(),
+                "mode" => level.mode = Some(value.to_string()),
+                "song" => level.song = Some(value.parse().map_err(not_a_number)?),
+                "c1001" => level.c1001 = Some(parse_color(value).map_err(invalid)?),
+                "c1002" => level.c1002 = Some(parse_color(value).map_err(invalid)?),
+                "c1004" => level.c1004 = Some(parse_color(value).map_err(invalid)?),
+                "bg" => level.bg = Some(value.parse().map_err(not_a_number)?),
+                "grnd" => level.grnd = Some(value.parse().map_err(not_a_number)?),
+                _ if INFO_KEYS.contains(&key) => level.info.load_value(key, value).map_err(invalid)?,
+                _ => match channel_key(key) {
+                    Some(channel) => {
+                        level.channels.insert(channel, parse_color(value).map_err(invalid)?);
+                    }
+                    None => level.extra.push((key.to_string(), value.to_string()))
+                }
+            }
+
+            level.key_order.push(key.to_string());
+            offset += pair.len() + 1;
+        }
+
+        if level.version.is_empty() {
+            return Err(LevelParseError::MissingVersion)
+        } else if !LEVEL_VERSIONS.contains(&level.version.as_str()) {
+            return Err(LevelParseError::UnknownVersion(level.version))
+        }
+
+        // This handles loading all the objects
+        // The objects start right after the ;;;
+        let mut offset = metadata.len() + 3;
+        if !object_string.is_empty() {
+            for (index, object) in object_string.split(';').enumerate() {
+                level.objects.push(parse_object(object, &level.version, index, offset)?);
+                offset += object.len() + 1;
+            }
+        }
+
+        Ok(level)
+    }
+
+    fn metadata_value(&self, key: &str) -> Option<String> {
+        let color = |color: (u8, u8, u8)| format!("{},{},{}", color.0, color.1, color.2);
+
+        match key {
+            "version" => Some(self.version.clone()),
+            "mode" => self.mode.clone(),
+            "song" => self.song.map(|song| song.to_string()),
+            "c1001" => self.c1001.map(color),
+            "c1002" => self.c1002.map(color),
+            "c1004" => self.c1004.map(color),
+            "bg" => self.bg.map(|bg| bg.to_string()),
+            "grnd" => self.grnd.map(|grnd| grnd.to_string()),
+            _ if INFO_KEYS.contains(&key) => self.info.value(key),
+            _ if channel_key(key).is_some() => channel_key(key)
+                .and_then(|channel| self.channels.get(&channel))
+                .map(|channel_color| color(*channel_color)),
+            _ => self.extra
+                .iter()
+                .find(|(extra_key, _)| extra_key == key)
+                .map(|(_, value)| value.clone())
+        }
+    }
+}
+
+// Shows why a level couldn't be loaded in red centered on the screen
+// It goes at the bottom of the screen unless it's given a y
+pub fn draw_level_error(d: &mut RaylibDrawHandle, error: &LevelParseError, y: Option<i32>) {
+    let text = error.to_string();
+    let x = d.get_screen_width() / 2 - d.measure_text(&text, 20) / 2;
+    let y = y.unwrap_or(d.get_screen_height() - 30);
+
+    d.draw_text(&text, x, y, 20, Color::RED);
+}
+
+// This is what turns a level back into a level string
+// Use level.to_string() to get it
+impl std::fmt::Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let mut keys: Vec<String> = self.key_order.clone();
+
+        // Keys that got set after the level was parsed still need to be written
+        for key in DEFAULT_KEY_ORDER.iter().chain(INFO_KEYS.iter()) {
+            if !keys.iter().any(|other| other == key) && self.metadata_value(key).is_some() {
+                keys.push(key.to_string());
+            }
+        }
+
+        for channel in self.channels.keys() {
+            let key = format!("c{}", channel);
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+
+        for (key, _) in &self.extra {
+            if !keys.contains(key) {
+                keys.push(key.clone());
+            }
+        }
+
+        let metadata: Vec<String> = keys
+            .iter()
+            .filter_map(|key| self.metadata_value(key).map(|value| format!("{}:{}", key, value)))
+            .collect();
+
+        let objects: Vec<String> = self.objects
+            .iter()
+            .map(|object| object_to_string(object, &self.version))
+            .collect();
+
+        write!(f, "{};;;{}", metadata.join(";"), objects.join(";"))
+    }
+}
+
+impl LevelParseError {
+    // Names and descriptions can have letters that take more than one byte
+    fn counted_in_characters(mut self, level_data: &str) -> LevelParseError {
+        match &mut self {
+            LevelParseError::InvalidMetadata { offset, .. } | LevelParseError::InvalidObject { offset, .. } => {
+                *offset = level_data.get(..*offset).map(|before| before.chars().count()).unwrap_or(*offset);
+            }
+            _ => {}
+        }
+
+        self
+    }
+}
+
+// These are the messages that get shown on screen when a level fails to load
+impl std::fmt::Display for LevelParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LevelParseError::MissingObjectSection => write!(f, "Level has no objects section (missing ;;;)"),
+            LevelParseError::MissingVersion => write!(f, "Level has no version"),
+            LevelParseError::UnknownVersion(version) => write!(f, "Level version {} not recognized, is it from a newer version?", version),
+            LevelParseError::InvalidMetadata { offset, pair, reason } => {
+                write!(f, "Metadata '{}' at character {}: {}", pair, offset, reason)
+            }
+            LevelParseError::InvalidObject { index, offset, field, reason } => {
+                write!(f, "Object {} at character {}: {} {}", index, offset, field, reason)
+            }
+            LevelParseError::InvalidResponse { field, reason } => write!(f, "Server response {} {}", field, reason)
+        }
+    }
+}
+
+// Function used for converting a level into text
+pub fn get_level_text(current_mode: &str, current_song: u8, bg_red: u8, bg_green: u8, bg_blue: u8, ground_red: u8, ground_green: u8, ground_blue: u8, color_channels: &ColorChannels, level_info: &LevelInfo, object_grid: &Vec<ObjectStruct>) -> String {
+    Level {
+        version: CURRENT_LEVEL_VERSION.to_string(),
+        mode: Some(current_mode.to_string()),
+        song: Some(current_song),
+        c1001: Some((bg_red, bg_green, bg_blue)),
+        c1002: Some((ground_red, ground_green, ground_blue)),
+        c1004: Some((255, 255, 255)),
+        channels: color_channels.clone(),
+        bg: Some(1),
+        grnd: Some(1),
+        info: level_info.clone(),
+        extra: vec![],
+        key_order: vec![],
+        objects: object_grid.clone()
+    }.to_string()
+}
+
+// Sets the modified time if the level is different from when it was last saved and gives back the text to save
+// That way saving a level you didn't change doesn't make it look like you did
+pub fn stamp_level_text(level_text: String, saved_text: &str, level_info: &mut LevelInfo, now: u64) -> String {
+    if level_text == saved_text {
+        return level_text;
+    }
+
+    level_info.changed(now);
+
+    match Level::parse(&level_text) {
+        Ok(mut level) => {
+            level.info = level_info.clone();
+            level.to_string()
+        }
+        Err(_) => level_text
+    }
 }
 
 // The function for loading levels
 pub fn load_level(
-    _level_metadata: &mut String,
-    _object_string: &mut String,
+    level_data: &str,
     object_grid: &mut Vec<ObjectStruct>,
 
     bg_red: &mut u8,
@@ -407,102 +981,79 @@ pub fn load_level(
     ground_green: &mut i32,
     ground_blue: &mut i32,
 
+    color_channels: &mut ColorChannels,
+    level_info: &mut LevelInfo,
+
     current_mode: &mut String,
 
     song_selected: bool,
     current_song: &mut u8,
     load_song: bool,
     song_if_song_not_selected: bool
-) -> String {
-    let mut level_version = "";
-
-    // This handles the level metadata
-    object_grid.clear();
-    let metadata_pairs: Vec<&str> = _level_metadata.split(';').collect();
-    *current_mode = "1".to_string();
-    for pair in metadata_pairs {
-        let key_value: Vec<&str> = pair.split(':').collect();
-        let key = key_value[0];
-        let value = key_value[1];
-
-        if key == "version" {
-            if value == "ALPHA" {
-                println!("Old level version detected.");
-                println!("Please pick a level thats a newer version as that version isnt supported anymore.");
-                break;
-            } else if value == "BETA"
-            || value == "1.3"
-            || value == "1.4"
-            || value == "1.5"
-            || value == "1.6" {
-                println!("Loading level...");
-            } else {
-                println!("Level version not recognized.");
-                println!("Is this level made in a newer version?");
-                return "version_invalid".to_string();
-            }
-
-            level_version = value;
-        } else if key == "c1001" {
-            let colors: Vec<&str> = value.split(',').collect();
-
-            *bg_red = colors[0].parse::<u8>().unwrap();
-            *bg_green = colors[1].parse::<u8>().unwrap();
-            *bg_blue = colors[2].parse::<u8>().unwrap();
-        } else if key == "c1002" {
-            let colors: Vec<&str> = value.split(',').collect();
-
-            *ground_red = colors[0].parse::<i32>().unwrap();
-            *ground_green = colors[1].parse::<i32>().unwrap();
-            *ground_blue = colors[2].parse::<i32>().unwrap();
-        } else if key == "song" {
-            if song_if_song_not_selected {
-                if !song_selected && load_song {
-                    *current_song = value.parse::<u8>().unwrap();
-                }
-            } else {
-                if load_song {
-                    *current_song = value.parse::<u8>().unwrap();
-                }
+) -> Result<(), LevelParseError> {
+    // Nothing gets changed if the level is broken
+    let level = Level::parse(level_data).inspect_err(|error| {
+        println!("Failed to load level: {}", error);
+    })?;
+
+    // Old levels get upgraded so the rest of the game only has to deal with the newest format
+    let level = upgrade_level(level);
+
+    println!("Loading level...");
+
+    if let Some((red, green, blue)) = level.c1001 {
+        *bg_red = red;
+        *bg_green = green;
+        *bg_blue = blue;
+    }
+
+    if let Some((red, green, blue)) = level.c1002 {
+        *ground_red = red as i32;
+        *ground_green = green as i32;
+        *ground_blue = blue as i32;
+    }
+
+    // Every level has its own channels so the ones from the last level get thrown away
+    *color_channels = level.channels;
+    *level_info = level.info;
+
+    if let Some(song) = level.song {
+        if song_if_song_not_selected {
+            if !song_selected && load_song {
+                *current_song = song;
+            }
+        } else {
+            if load_song {
+                *current_song = song;
             }
-        } else if key == "mode" {
-            *current_mode = value.to_string();
         }
     }
 
-    // This handles loading all the objects
-    let object_list: Vec<&str> = _object_string.split(';').collect();
-    for object in object_list {
-        let xyrid: Vec<&str> = object.split(':').collect();
-        let obj_id = if level_version == "BETA" { xyrid[3].parse().unwrap() } else { xyrid[5].parse::<u32>().unwrap() };
-
-        if !_object_string.is_empty() {
-            object_grid.push(ObjectStruct {
-                y: xyrid[0].parse::<i32>().unwrap(),
-                x: xyrid[1].parse::<i32>().unwrap(),
-                rotation: xyrid[2].parse::<i16>().unwrap(),
-                no_touch: if level_version == "BETA" { 0 } else { xyrid[3].parse().unwrap() },
-                hide: if level_version == "BETA" { 0 } else { xyrid[4].parse().unwrap() },
-                id: obj_id,
-                selected: false,
-                properties: if obj_id == 23 && level_version != "BETA" {Some(
-                    vec![
-                        xyrid[6].to_string(),
-                        xyrid[7].to_string(),
-                        xyrid[8].to_string(),
-                        xyrid[9].to_string()
-                    ]
-                )} else {
-                    None
-                }
-            });
+    *current_mode = level.mode.unwrap_or("1".to_string());
+    *object_grid = level.objects;
+
+    Ok(())
+}
+
+// Plays the level's own song if it has one that works and the picked song if it doesn't
+pub fn open_level_music(level_info: &LevelInfo, picked_song: &str) -> Decoder<BufReader<File>> {
+    if let Some(path) = level_info.song_path() {
+        let music = File::open(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|file| Decoder::new(BufReader::new(file)).map_err(|error| error.to_string()));
+
+        match music {
+            Ok(music) => return music,
+            Err(error) => println!("Failed to play {}, using the picked song instead: {}", path, error)
         }
     }
 
-    return "ok".to_string()
+    let level_music_file = BufReader::new(File::open(picked_song).expect("Failed to open MP3 file"));
+    Decoder::new(level_music_file).expect("Failed to decode MP3 file")
 }
 
 // This is the function for parsing the level download response from the servers
+// The response is name;desc;diff;rated;creator;;;;;level data
 pub fn parse_level_download_response(
     level_download_result: String,
     online_level_name: &mut String,
@@ -511,29 +1062,104 @@ pub fn parse_level_download_response(
     online_level_rated: &mut bool,
     online_level_creator: &mut String,
     online_level_data: &mut String
-) {
+) -> Result<(), LevelParseError> {
     let level_download_result_parts: Vec<&str> = level_download_result.split(";;;;;").collect();
     let level_download_result_parts_empty_user: Vec<&str> = level_download_result.split(";;;;;;").collect();
     let name_desc: Vec<&str> = level_download_result_parts[0].split(";").collect();
 
+    if name_desc.len() < 4 {
+        return Err(LevelParseError::InvalidResponse {
+            field: "level info",
+            reason: format!("needs at least 4 fields but has {}", name_desc.len())
+        })
+    }
+
+    let diff: u8 = name_desc[2].parse().map_err(|_| LevelParseError::InvalidResponse {
+        field: "difficulty",
+        reason: format!("'{}' is not a valid number", name_desc[2])
+    })?;
+
+    let level_data = if name_desc.len() > 4 {
+        level_download_result_parts.get(1)
+    } else {
+        level_download_result_parts_empty_user.get(1)
+    }.ok_or(LevelParseError::InvalidResponse {
+        field: "level data",
+        reason: "is missing".to_string()
+    })?;
+
     *online_level_name = name_desc[0].to_string();
     *online_level_desc = name_desc[1].to_string();
-    *online_level_diff = name_desc[2].parse().unwrap();
+    *online_level_diff = diff;
     if name_desc.len() > 4 {
-        *online_level_creator = name_desc[4].parse().unwrap();
+        *online_level_creator = name_desc[4].to_string();
     } else {
         *online_level_creator = "".to_string();
     }
 
-    if name_desc.len() > 4 {
-        *online_level_data = level_download_result_parts[1].to_string();
-    } else {
-        *online_level_data = level_download_result_parts_empty_user[1].to_string();
-    }
+    *online_level_data = level_data.to_string();
 
     if name_desc[3] == "0" {
         *online_level_rated = false
     } else {
         *online_level_rated = true
     }
-}
\ No newline at end of file
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Every level that ships with the game and every migration golden file
+    fn level_files() -> Vec<std::path::PathBuf> {
+        let mut files = vec![];
+
+        for folder in ["save-data/main-levels", "tests/golden"] {
+            let folder = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(folder);
+            for entry in std::fs::read_dir(&folder).unwrap() {
+                let path = entry.unwrap().path();
+                if path.extension().is_some_and(|extension| extension == "txt") {
+                    files.push(path);
+                }
+            }
+        }
+
+        files
+    }
+
+    #[test]
+    fn level_files_round_trip() {
+        let files = level_files();
+        assert!(files.len() >= 9);
+
+        for path in files {
+            let level_data = std::fs::read_to_string(&path).unwrap();
+            let level = Level::parse(&level_data).unwrap_or_else(|error| panic!("{}: {}", path.display(), error));
+
+            assert_eq!(level.to_string(), level_data, "{} changed after saving", path.display());
+        }
+    }
+
+    #[test]
+    fn error_offsets_count_characters() {
+        assert_eq!(
+            Level::parse("version:1.6;name:héllo;;;480:960:0:0:0:x"),
+            Err(LevelParseError::InvalidObject {
+                index: 0,
+                offset: 39,
+                field: "id",
+                reason: "'x' is not a valid number".to_string()
+            })
+        );
+        assert_eq!(
+            Level::parse("version:1.6;name:é;mode:1;bg:x;;;"),
+            Err(LevelParseError::InvalidMetadata {
+                offset: 26,
+                pair: "bg:x".to_string(),
+                reason: "'x' is not a valid number".to_string()
+            })
+        );
+    }
+}
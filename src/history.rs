@@ -0,0 +1,344 @@
+use crate::types::ObjectStruct;
+
+// Undo and redo for the editor
+// Every change to the level goes through here so it can be put back the way it was
+// Only the objects that changed get stored instead of the whole level
+
+// Old edits get thrown away once there are this many
+const MAX_EDITS: usize = 200;
+// Or once the edits are holding this many objects between them so deleting huge levels over and over can't eat all the memory
+const MAX_STORED_OBJECTS: usize = 50_000;
+// Moving the same objects again within this many seconds counts as the same move
+const MOVE_MERGE_TIME: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EditKind {
+    Place,
+    Delete,
+    Move,
+    Rotate,
+    Toggle,
+    Property
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Change {
+    // Objects that got added and where they went
+    Added(Vec<(usize, ObjectStruct)>),
+    // Objects that got removed and where they were
+    Removed(Vec<(usize, ObjectStruct)>),
+    // Objects that got changed and what they were before and after
+    Modified(Vec<(usize, ObjectStruct, ObjectStruct)>)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Edit {
+    kind: EditKind,
+    change: Change,
+    time: f64
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditHistory {
+    undo_stack: Vec<Edit>,
+    redo_stack: Vec<Edit>,
+    // Set to false after undoing so the next move doesn't get merged into an older one
+    can_merge: bool,
+    // Goes up every time the level changes so other stuff knows when to update
+    revision: u64
+}
+
+impl Change {
+    fn object_count(&self) -> usize {
+        match self {
+            Change::Added(objects) | Change::Removed(objects) => objects.len(),
+            Change::Modified(objects) => objects.len() * 2
+        }
+    }
+
+    // The indexes are always from lowest to highest
+    // Adding goes from the front and removing goes from the back so the indexes stay right
+    fn apply(&self, object_grid: &mut Vec<ObjectStruct>) {
+        match self {
+            Change::Added(objects) => {
+                for (index, object) in objects {
+                    object_grid.insert(*index, object.clone());
+                }
+            }
+            Change::Removed(objects) => {
+                for (index, _) in objects.iter().rev() {
+                    object_grid.remove(*index);
+                }
+            }
+            Change::Modified(objects) => {
+                for (index, _, after) in objects {
+                    set_keeping_selection(&mut object_grid[*index], after);
+                }
+            }
+        }
+    }
+
+    fn revert(&self, object_grid: &mut Vec<ObjectStruct>) {
+        match self {
+            Change::Added(objects) => Change::Removed(objects.clone()).apply(object_grid),
+            Change::Removed(objects) => Change::Added(objects.clone()).apply(object_grid),
+            Change::Modified(objects) => {
+                for (index, before, _) in objects {
+                    set_keeping_selection(&mut object_grid[*index], before);
+                }
+            }
+        }
+    }
+}
+
+// Undoing shouldn't change what you have selected
+fn set_keeping_selection(object: &mut ObjectStruct, new_object: &ObjectStruct) {
+    let selected = object.selected;
+    *object = new_object.clone();
+    object.selected = selected;
+}
+
+impl EditHistory {
+    pub fn new() -> EditHistory {
+        EditHistory {
+            undo_stack: vec![],
+            redo_stack: vec![],
+            can_merge: false,
+            revision: 0
+        }
+    }
+
+    // Forgets everything like when a different level gets opened
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.can_merge = false;
+        self.revision += 1;
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    fn record(&mut self, kind: EditKind, change: Change, time: f64) {
+        self.redo_stack.clear();
+        self.revision += 1;
+
+        // Moving a selection a few times in a row only needs one undo
+        if let (Some(last), Change::Modified(objects)) = (self.undo_stack.last_mut(), &change) {
+            if let Change::Modified(last_objects) = &mut last.change {
+                let same_objects = last_objects.len() == objects.len()
+                    && last_objects.iter().zip(objects).all(|(last_object, object)| last_object.0 == object.0);
+
+                if self.can_merge
+                && kind == EditKind::Move
+                && last.kind == EditKind::Move
+                && same_objects
+                && time - last.time < MOVE_MERGE_TIME {
+                    for (last_object, object) in last_objects.iter_mut().zip(objects) {
+                        last_object.2 = object.2.clone();
+                    }
+                    last.time = time;
+                    return;
+                }
+            }
+        }
+
+        self.undo_stack.push(Edit { kind, change, time });
+        self.can_merge = true;
+
+        while self.undo_stack.len() > MAX_EDITS
+        || (self.undo_stack.len() > 1 && self.stored_objects() > MAX_STORED_OBJECTS) {
+            self.undo_stack.remove(0);
+        }
+    }
+
+    fn stored_objects(&self) -> usize {
+        self.undo_stack.iter().chain(&self.redo_stack).map(|edit| edit.change.object_count()).sum()
+    }
+
+    // Puts a new object at the end of the level
+    pub fn place(&mut self, object_grid: &mut Vec<ObjectStruct>, object: ObjectStruct, time: f64) {
+        self.add(object_grid, vec![object], time);
+    }
+
+    // Puts a bunch of new objects at the end of the level as one edit
+    pub fn add(&mut self, object_grid: &mut Vec<ObjectStruct>, objects: Vec<ObjectStruct>, time: f64) {
+        if objects.is_empty() {
+            return;
+        }
+
+        let start = object_grid.len();
+        let change = Change::Added(objects.into_iter().enumerate().map(|(index, object)| (start + index, object)).collect());
+        change.apply(object_grid);
+        self.record(EditKind::Place, change, time);
+    }
+
+    // Removes every object that matches
+    pub fn delete_where(
+        &mut self,
+        object_grid: &mut Vec<ObjectStruct>,
+        time: f64,
+        should_delete: impl Fn(&ObjectStruct) -> bool
+    ) {
+        let removed: Vec<(usize, ObjectStruct)> = object_grid.iter()
+            .enumerate()
+            .filter(|(_, object)| should_delete(object))
+            .map(|(index, object)| (index, object.clone()))
+            .collect();
+
+        if removed.is_empty() {
+            return;
+        }
+
+        let change = Change::Removed(removed);
+        change.apply(object_grid);
+        self.record(EditKind::Delete, change, time);
+    }
+
+    pub fn delete_at(&mut self, object_grid: &mut Vec<ObjectStruct>, index: usize, time: f64) {
+        if index < object_grid.len() {
+            let change = Change::Removed(vec![(index, object_grid[index].clone())]);
+            change.apply(object_grid);
+            self.record(EditKind::Delete, change, time);
+        }
+    }
+
+    // Runs a change on every selected object
+    // Objects that didn't actually change don't get stored
+    pub fn change_selected(
+        &mut self,
+        object_grid: &mut [ObjectStruct],
+        kind: EditKind,
+        time: f64,
+        mut change: impl FnMut(&mut ObjectStruct)
+    ) {
+        self.change_where(object_grid, kind, time, |object| object.selected, &mut change);
+    }
+
+    pub fn change_where(
+        &mut self,
+        object_grid: &mut [ObjectStruct],
+        kind: EditKind,
+        time: f64,
+        should_change: impl Fn(&ObjectStruct) -> bool,
+        mut change: impl FnMut(&mut ObjectStruct)
+    ) {
+        let mut modified: Vec<(usize, ObjectStruct, ObjectStruct)> = vec![];
+
+        for (index, object) in object_grid.iter_mut().enumerate() {
+            if should_change(object) {
+                let before = object.clone();
+                change(object);
+
+                if *object != before {
+                    modified.push((index, before, object.clone()));
+                }
+            }
+        }
+
+        if !modified.is_empty() {
+            self.record(kind, Change::Modified(modified), time);
+        }
+    }
+
+    // Gives back what kind of edit got undone so the editor can say what happened
+    pub fn undo(&mut self, object_grid: &mut Vec<ObjectStruct>) -> Option<EditKind> {
+        let edit = self.undo_stack.pop()?;
+        edit.change.revert(object_grid);
+        let kind = edit.kind;
+        self.redo_stack.push(edit);
+        self.can_merge = false;
+        self.revision += 1;
+
+        Some(kind)
+    }
+
+    pub fn redo(&mut self, object_grid: &mut Vec<ObjectStruct>) -> Option<EditKind> {
+        let edit = self.redo_stack.pop()?;
+        edit.change.apply(object_grid);
+        let kind = edit.kind;
+        self.undo_stack.push(edit);
+        self.can_merge = false;
+        self.revision += 1;
+
+        Some(kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(x: i32, id: u32) -> ObjectStruct {
+        ObjectStruct {
+            y: 480,
+            x,
+            rotation: 0,
+            no_touch: 0,
+            hide: 0,
+            color_channel: 0,
+            id,
+            selected: false,
+            properties: None
+        }
+    }
+
+    #[test]
+    fn undo_and_redo_put_the_level_back() {
+        let mut history = EditHistory::new();
+        let mut level = vec![object(0, 1), object(40, 2), object(80, 1)];
+        let original = level.clone();
+
+        history.place(&mut level, object(120, 3), 0.0);
+        history.delete_where(&mut level, 1.0, |object| object.id == 1);
+        level[0].selected = true;
+        history.change_selected(&mut level, EditKind::Rotate, 2.0, |object| object.rotation += 90);
+        let edited = level.clone();
+        assert_eq!(level.iter().map(|object| object.id).collect::<Vec<_>>(), vec![2, 3]);
+
+        while history.undo(&mut level).is_some() {}
+        for object in &mut level {
+            object.selected = false;
+        }
+        assert_eq!(level, original);
+
+        while history.redo(&mut level).is_some() {}
+        level[0].selected = true;
+        assert_eq!(level, edited);
+    }
+
+    #[test]
+    fn moving_the_same_objects_quickly_is_one_undo() {
+        let mut history = EditHistory::new();
+        let mut level = vec![object(0, 1), object(40, 1)];
+        level[0].selected = true;
+        level[1].selected = true;
+
+        for press in 0..5 {
+            history.change_selected(&mut level, EditKind::Move, press as f64 * 0.2, |object| object.x += 40);
+        }
+        // Too long after the last one
+        history.change_selected(&mut level, EditKind::Move, 10.0, |object| object.x += 40);
+
+        assert_eq!(level[0].x, 240);
+        history.undo(&mut level);
+        assert_eq!(level[0].x, 200);
+        history.undo(&mut level);
+        assert_eq!((level[0].x, level[1].x), (0, 40));
+        assert!(history.undo_stack.is_empty());
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let mut history = EditHistory::new();
+        let mut level = vec![];
+
+        for x in 0..MAX_EDITS as i32 + 50 {
+            history.place(&mut level, object(x * 40, 1), x as f64);
+        }
+
+        assert_eq!(history.undo_stack.len(), MAX_EDITS);
+        assert!(history.stored_objects() <= MAX_STORED_OBJECTS);
+    }
+}
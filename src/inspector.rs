@@ -0,0 +1,342 @@
+use crate::colors::MAX_COLOR_CHANNEL;
+use crate::objects::{ObjectRegistry, PropertyKind};
+use crate::types::ObjectStruct;
+
+// The object settings popup in the editor
+// It shows every field the selected objects have and changing one changes it on all of them
+// The popup itself is drawn in main.rs and this is the part that knows about the objects
+
+// Where a field's value lives on an object
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldTarget {
+    X,
+    Y,
+    Rotation,
+    NoTouch,
+    Hide,
+    // Every object has one and 0 means it doesn't use a channel
+    ColorChannel,
+    // Only objects with this id have this property
+    Property { id: u32, index: usize }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldKind {
+    Number { min: i64, max: i64 },
+    Decimal { min: f32, max: f32 },
+    Toggle,
+    // The value that gets stored and the name that gets shown
+    Choice(Vec<(String, String)>)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InspectorField {
+    pub name: String,
+    pub kind: FieldKind,
+    pub target: FieldTarget,
+    // None when the selected objects don't all have the same value
+    pub value: Option<String>
+}
+
+impl FieldTarget {
+    pub fn applies_to(&self, object: &ObjectStruct) -> bool {
+        match self {
+            FieldTarget::Property { id, .. } => object.id == *id,
+            _ => true
+        }
+    }
+
+    fn get(&self, object: &ObjectStruct, registry: &ObjectRegistry) -> String {
+        match self {
+            FieldTarget::X => object.x.to_string(),
+            FieldTarget::Y => object.y.to_string(),
+            FieldTarget::Rotation => object.rotation.to_string(),
+            FieldTarget::NoTouch => object.no_touch.to_string(),
+            FieldTarget::Hide => object.hide.to_string(),
+            FieldTarget::ColorChannel => object.color_channel.to_string(),
+            // Objects from older levels can be missing newer properties so they count as having the default
+            FieldTarget::Property { index, .. } => object.properties.as_ref()
+                .and_then(|properties| properties.get(*index))
+                .cloned()
+                .unwrap_or_else(|| default_property(object, *index, registry))
+        }
+    }
+
+    // The value has already been checked by InspectorField::parse
+    fn set(&self, object: &mut ObjectStruct, value: &str, registry: &ObjectRegistry) {
+        match self {
+            FieldTarget::X => if let Ok(x) = value.parse() { object.x = x },
+            FieldTarget::Y => if let Ok(y) = value.parse() { object.y = y },
+            FieldTarget::Rotation => if let Ok(rotation) = value.parse() { object.rotation = rotation },
+            FieldTarget::NoTouch => if let Ok(no_touch) = value.parse() { object.no_touch = no_touch },
+            FieldTarget::Hide => if let Ok(hide) = value.parse() { object.hide = hide },
+            FieldTarget::ColorChannel => if let Ok(channel) = value.parse() { object.color_channel = channel },
+            FieldTarget::Property { index, .. } => {
+                let mut properties = object.properties.take().unwrap_or_default();
+                let property_count = registry.get(object.id)
+                    .map(|object_type| object_type.properties.len())
+                    .unwrap_or(0)
+                    .max(index + 1);
+
+                // Anything missing gets filled in so the property ends up in the right spot and the object is still valid
+                while properties.len() < property_count {
+                    properties.push(default_property(object, properties.len(), registry));
+                }
+                properties[*index] = value.to_string();
+
+                object.properties = Some(properties);
+            }
+        }
+    }
+}
+
+fn default_property(object: &ObjectStruct, index: usize, registry: &ObjectRegistry) -> String {
+    registry.get(object.id)
+        .and_then(|object_type| object_type.properties.get(index))
+        .map(|property| property.default.clone())
+        .unwrap_or_default()
+}
+
+impl InspectorField {
+    fn new(name: &str, kind: FieldKind, target: FieldTarget) -> InspectorField {
+        InspectorField {
+            name: name.to_string(),
+            kind,
+            target,
+            value: None
+        }
+    }
+
+    // Checks what was typed in and gives back the value to store
+    pub fn parse(&self, text: &str) -> Result<String, String> {
+        let text = text.trim();
+
+        match &self.kind {
+            FieldKind::Number { min, max } => match text.parse::<i64>() {
+                Ok(number) if number >= *min && number <= *max => Ok(number.to_string()),
+                _ => Err(format!("{} has to be a number from {} to {}", self.name, min, max))
+            },
+            FieldKind::Decimal { min, max } => match text.parse::<f32>() {
+                Ok(number) if number >= *min && number <= *max => Ok(number.to_string()),
+                _ => Err(format!("{} has to be a number from {} to {}", self.name, min, max))
+            },
+            FieldKind::Toggle => match text {
+                "0" | "1" => Ok(text.to_string()),
+                _ => Err(format!("{} has to be 0 or 1", self.name))
+            },
+            FieldKind::Choice(choices) => match choices.iter().find(|(value, _)| value == text) {
+                Some((value, _)) => Ok(value.clone()),
+                None => Err(format!(
+                    "{} has to be {}",
+                    self.name,
+                    choices.iter().map(|(value, label)| format!("{} ({})", value, label)).collect::<Vec<_>>().join(" or ")
+                ))
+            }
+        }
+    }
+
+    // What gets shown on the button for toggles and choices
+    pub fn label(&self) -> String {
+        let value = match &self.value {
+            Some(value) => value,
+            None => return "Mixed".to_string()
+        };
+
+        match &self.kind {
+            FieldKind::Number { .. } | FieldKind::Decimal { .. } => value.clone(),
+            FieldKind::Toggle => if value == "1" { "On".to_string() } else { "Off".to_string() },
+            FieldKind::Choice(choices) => choices.iter()
+                .find(|(choice, _)| choice == value)
+                .map(|(_, label)| label.clone())
+                .unwrap_or_else(|| value.clone())
+        }
+    }
+
+    // What clicking a toggle or choice changes it to
+    // Mixed values all get set to the first option
+    pub fn next_value(&self) -> String {
+        match &self.kind {
+            FieldKind::Number { .. } | FieldKind::Decimal { .. } => self.value.clone().unwrap_or_default(),
+            FieldKind::Toggle => if self.value.as_deref() == Some("0") { "1".to_string() } else { "0".to_string() },
+            FieldKind::Choice(choices) => {
+                let next = self.value.as_ref()
+                    .and_then(|value| choices.iter().position(|(choice, _)| choice == value))
+                    .map(|index| (index + 1) % choices.len())
+                    .unwrap_or(0);
+
+                choices.get(next).map(|(value, _)| value.clone()).unwrap_or_default()
+            }
+        }
+    }
+
+    // Numbers get typed in and everything else gets clicked through
+    pub fn is_typed(&self) -> bool {
+        matches!(self.kind, FieldKind::Number { .. } | FieldKind::Decimal { .. })
+    }
+
+    pub fn applies_to(&self, object: &ObjectStruct) -> bool {
+        object.selected && self.target.applies_to(object)
+    }
+
+    pub fn apply(&self, object: &mut ObjectStruct, value: &str, registry: &ObjectRegistry) {
+        self.target.set(object, value, registry);
+    }
+}
+
+// Every field the selected objects have with what they are set to
+// Properties only show up for the kinds of objects that are selected
+pub fn inspect(object_grid: &[ObjectStruct], registry: &ObjectRegistry) -> Vec<InspectorField> {
+    let mut fields = vec![
+        InspectorField::new("X", FieldKind::Number { min: i32::MIN as i64, max: i32::MAX as i64 }, FieldTarget::X),
+        InspectorField::new("Y", FieldKind::Number { min: i32::MIN as i64, max: i32::MAX as i64 }, FieldTarget::Y),
+        InspectorField::new("Rotation", FieldKind::Number { min: -360, max: 360 }, FieldTarget::Rotation),
+        InspectorField::new("No Touch", FieldKind::Toggle, FieldTarget::NoTouch),
+        InspectorField::new("Hide", FieldKind::Toggle, FieldTarget::Hide),
+        InspectorField::new("Color Channel", FieldKind::Number { min: 0, max: MAX_COLOR_CHANNEL as i64 }, FieldTarget::ColorChannel)
+    ];
+
+    let mut ids: Vec<u32> = vec![];
+    for object in object_grid.iter().filter(|object| object.selected) {
+        if !ids.contains(&object.id) && registry.get(object.id).is_some_and(|object_type| !object_type.properties.is_empty()) {
+            ids.push(object.id);
+        }
+    }
+
+    for id in &ids {
+        let object_type = match registry.get(*id) {
+            Some(object_type) => object_type,
+            None => continue
+        };
+
+        for (index, property) in object_type.properties.iter().enumerate() {
+            // With different kinds of objects selected the names say which object they are for
+            let name = if ids.len() > 1 {
+                format!("{} {}", object_type.name, property.name)
+            } else {
+                property.name.clone()
+            };
+            let kind = match &property.kind {
+                PropertyKind::Number { min, max } => FieldKind::Number { min: *min, max: *max },
+                PropertyKind::Decimal { min, max } => FieldKind::Decimal { min: *min, max: *max },
+                PropertyKind::Choice(choices) => FieldKind::Choice(choices.clone())
+            };
+
+            fields.push(InspectorField::new(&name, kind, FieldTarget::Property { id: *id, index }));
+        }
+    }
+
+    for field in &mut fields {
+        let mut values = object_grid.iter()
+            .filter(|object| field.applies_to(object))
+            .map(|object| field.target.get(object, registry));
+
+        field.value = values.next().filter(|first| values.all(|value| value == *first));
+    }
+
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(x: i32, id: u32, properties: Option<Vec<&str>>) -> ObjectStruct {
+        ObjectStruct {
+            y: 480,
+            x,
+            rotation: 0,
+            no_touch: 0,
+            hide: 0,
+            color_channel: 0,
+            id,
+            selected: true,
+            properties: properties.map(|properties| properties.into_iter().map(|property| property.to_string()).collect())
+        }
+    }
+
+    fn field<'a>(fields: &'a [InspectorField], name: &str) -> &'a InspectorField {
+        fields.iter().find(|field| field.name == name).unwrap()
+    }
+
+    #[test]
+    fn mixed_values_are_shown() {
+        let registry = ObjectRegistry::new();
+        let level = vec![
+            object(0, 23, Some(vec!["255", "0", "0", "1"])),
+            object(40, 23, Some(vec!["255", "10", "0", "2"])),
+            object(80, 1, None)
+        ];
+        let fields = inspect(&level, &registry);
+
+        assert_eq!(field(&fields, "X").value, None);
+        assert_eq!(field(&fields, "Y").value, Some("480".to_string()));
+        assert_eq!(field(&fields, "Red").value, Some("255".to_string()));
+        assert_eq!(field(&fields, "Green").label(), "Mixed");
+        assert_eq!(field(&fields, "Changes").next_value(), "1");
+
+        // Only the selected objects count
+        let mut level = level;
+        level[1].selected = false;
+        let fields = inspect(&level, &registry);
+        assert_eq!(field(&fields, "Changes").label(), "Background");
+        assert_eq!(field(&fields, "Changes").next_value(), "2");
+    }
+
+    #[test]
+    fn changes_go_to_every_selected_object() {
+        let registry = ObjectRegistry::new();
+        let mut level = vec![
+            object(0, 23, Some(vec!["255", "0", "0", "1"])),
+            object(40, 23, None),
+            object(80, 1, None)
+        ];
+        let fields = inspect(&level, &registry);
+        let blue = field(&fields, "Blue");
+        let value = blue.parse(" 200 ").unwrap();
+
+        for object in level.iter_mut().filter(|object| blue.applies_to(object)) {
+            blue.apply(object, &value, &registry);
+        }
+
+        // Older objects get the properties that were added since then
+        assert_eq!(level[0].properties, Some(vec!["255", "0", "200", "1", "0", "0", "1"].into_iter().map(String::from).collect()));
+        // Missing properties get filled in with the defaults
+        assert_eq!(level[1].properties, Some(vec!["50", "50", "200", "1", "0", "0", "1"].into_iter().map(String::from).collect()));
+        assert_eq!(level[2].properties, None);
+    }
+
+    #[test]
+    fn any_object_can_have_a_color_channel() {
+        let registry = ObjectRegistry::new();
+        let mut level = vec![object(0, 1, None), object(40, 23, Some(vec!["255", "0", "0", "1"]))];
+        let fields = inspect(&level, &registry);
+        let channel = field(&fields, "Color Channel");
+        assert_eq!(channel.value, Some("0".to_string()));
+
+        for object in level.iter_mut() {
+            channel.apply(object, "12", &registry);
+        }
+        assert_eq!((level[0].color_channel, level[1].color_channel), (12, 12));
+        // The channel doesn't go in the properties so the trigger's properties are left alone
+        assert_eq!(level[0].properties, None);
+        assert_eq!(level[1].properties.as_ref().map(|properties| properties.len()), Some(4));
+        assert_eq!(field(&inspect(&level, &registry), "Color Channel").value, Some("12".to_string()));
+
+        channel.apply(&mut level[0], "0", &registry);
+        assert_eq!(level[0].color_channel, 0);
+    }
+
+    #[test]
+    fn bad_values_are_rejected() {
+        let fields = inspect(&[object(0, 23, Some(vec!["1", "2", "3", "1"]))], &ObjectRegistry::new());
+
+        assert!(field(&fields, "Red").parse("256").is_err());
+        assert!(field(&fields, "Red").parse("red").is_err());
+        assert!(field(&fields, "Hide").parse("2").is_err());
+        assert!(field(&fields, "Changes").parse("4").is_err());
+        assert!(field(&fields, "Target Channel").parse("1000").is_err());
+        assert!(field(&fields, "Fade Time").parse("-1").is_err());
+        assert_eq!(field(&fields, "Fade Time").parse("0.50"), Ok("0.5".to_string()));
+        assert_eq!(field(&fields, "X").parse("-40"), Ok("-40".to_string()));
+    }
+}
@@ -0,0 +1,214 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// The name, description and such that a level has in its metadata
+// None of it changes how the level plays
+// Text can have ; and : in it so those get written as %3B and %3A to not break the level string
+
+// Every metadata key that goes in here in the order they get written
+pub const INFO_KEYS: [&str; 7] = ["name", "desc", "author", "songfile", "diff", "created", "modified"];
+
+// Custom songs have to be in here so a downloaded level can't point anywhere else
+pub const SONGS_FOLDER: &str = "./Music";
+
+// Same as the difficulties you can pick when uploading
+pub const MAX_DIFFICULTY: u8 = 5;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LevelInfo {
+    // Empty text doesn't get written to the level
+    pub name: String,
+    pub desc: String,
+    pub author: String,
+    // A song in the Music folder that gets played instead of the picked one
+    pub song_file: String,
+    // The difficulty the creator is going for
+    pub difficulty: Option<u8>,
+    // Seconds since 1970
+    // Levels from before these existed get a created time the first time they are saved
+    pub created: Option<u64>,
+    pub modified: Option<u64>
+}
+
+impl LevelInfo {
+    // Reads one of the INFO_KEYS from the level string
+    pub fn load_value(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let not_a_number = |_| format!("'{}' is not a valid number", value);
+
+        match key {
+            "name" => self.name = unescape_text(value),
+            "desc" => self.desc = unescape_text(value),
+            "author" => self.author = unescape_text(value),
+            "songfile" => self.song_file = unescape_text(value),
+            "diff" => {
+                let difficulty: u8 = value.parse().map_err(not_a_number)?;
+                if difficulty > MAX_DIFFICULTY {
+                    return Err(format!("'{}' is not a difficulty from 0 to {}", value, MAX_DIFFICULTY));
+                }
+                self.difficulty = Some(difficulty);
+            }
+            "created" => self.created = Some(value.parse().map_err(not_a_number)?),
+            "modified" => self.modified = Some(value.parse().map_err(not_a_number)?),
+            _ => return Err("is not level info".to_string())
+        }
+
+        Ok(())
+    }
+
+    // What gets written to the level string for a key
+    pub fn value(&self, key: &str) -> Option<String> {
+        let text = |text: &str| if text.is_empty() { None } else { Some(escape_text(text)) };
+
+        match key {
+            "name" => text(&self.name),
+            "desc" => text(&self.desc),
+            "author" => text(&self.author),
+            "songfile" => text(&self.song_file),
+            "diff" => self.difficulty.map(|difficulty| difficulty.to_string()),
+            "created" => self.created.map(|time| time.to_string()),
+            "modified" => self.modified.map(|time| time.to_string()),
+            _ => None
+        }
+    }
+
+    // Only plain file names count so the song can't come from outside the Music folder
+    pub fn song_path(&self) -> Option<String> {
+        if self.song_file.is_empty() || self.song_file.contains(['/', '\\']) || self.song_file.starts_with('.') {
+            return None;
+        }
+
+        Some(format!("{}/{}", SONGS_FOLDER, self.song_file))
+    }
+
+    // Called when the level gets saved with something different in it
+    pub fn changed(&mut self, now: u64) {
+        self.created.get_or_insert(now);
+        self.modified = Some(now);
+    }
+}
+
+// A % only gets escaped when it would look like an escape otherwise
+// That way hand written text like 50% off gets saved the same way it was loaded
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::new();
+
+    for (index, character) in text.char_indices() {
+        match character {
+            ';' => escaped.push_str("%3B"),
+            ':' => escaped.push_str("%3A"),
+            '%' if matches!(text.get(index + 1..index + 3), Some("25" | "3B" | "3A")) => escaped.push_str("%25"),
+            _ => escaped.push(character)
+        }
+    }
+
+    escaped
+}
+
+// Anything that isn't one of the three escapes gets left alone so hand written levels with a % still load
+fn unescape_text(text: &str) -> String {
+    let mut unescaped = String::new();
+    let mut rest = text;
+
+    while let Some(index) = rest.find('%') {
+        unescaped.push_str(&rest[..index]);
+        rest = &rest[index..];
+
+        let (character, length) = match rest.get(..3) {
+            Some("%25") => ('%', 3),
+            Some("%3B") => (';', 3),
+            Some("%3A") => (':', 3),
+            _ => ('%', 1)
+        };
+        unescaped.push(character);
+        rest = &rest[length..];
+    }
+
+    unescaped.push_str(rest);
+    unescaped
+}
+
+pub fn unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or(0)
+}
+
+pub fn from_unix_time(time: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(pairs: &[(&str, &str)]) -> Result<LevelInfo, String> {
+        let mut info = LevelInfo::default();
+        for (key, value) in pairs {
+            info.load_value(key, value)?;
+        }
+        Ok(info)
+    }
+
+    #[test]
+    fn text_with_separators_round_trips() {
+        let info = LevelInfo {
+            name: "Rays: 100%".to_string(),
+            desc: "a;b %3B %%3A".to_string(),
+            author: "Puppet".to_string(),
+            song_file: "my song.mp3".to_string(),
+            difficulty: Some(3),
+            created: Some(10),
+            modified: Some(20)
+        };
+
+        let pairs: Vec<(&str, String)> = INFO_KEYS.iter().filter_map(|key| Some((*key, info.value(key)?))).collect();
+        assert!(pairs.iter().all(|(_, value)| !value.contains(';') && !value.contains(':')));
+
+        let pairs: Vec<(&str, &str)> = pairs.iter().map(|(key, value)| (*key, value.as_str())).collect();
+        assert_eq!(load(&pairs), Ok(info));
+    }
+
+    #[test]
+    fn beta_levels_keep_their_name() {
+        let info = load(&[("name", "hi"), ("desc", "testing level loading")]).unwrap();
+
+        assert_eq!((info.name.as_str(), info.desc.as_str()), ("hi", "testing level loading"));
+        assert_eq!(info.value("name"), Some("hi".to_string()));
+        assert_eq!(info.value("author"), None);
+        assert_eq!(load(&[("name", "50% off")]).unwrap().name, "50% off");
+    }
+
+    #[test]
+    fn hand_written_percents_stay_the_same() {
+        for text in ["50% off", "100%", "%", "%3", "%2B"] {
+            let info = load(&[("name", text)]).unwrap();
+            assert_eq!(info.value("name").as_deref(), Some(text));
+        }
+
+        assert_eq!(escape_text("%3B;"), "%253B%3B");
+    }
+
+    #[test]
+    fn songs_only_come_from_the_music_folder() {
+        let song = |song_file: &str| LevelInfo { song_file: song_file.to_string(), ..Default::default() }.song_path();
+
+        assert_eq!(song("my song.mp3"), Some("./Music/my song.mp3".to_string()));
+        assert_eq!(song(""), None);
+        assert_eq!(song("../save-data/save.json"), None);
+        assert_eq!(song(".."), None);
+        assert_eq!(song("C:\\song.mp3"), None);
+    }
+
+    #[test]
+    fn broken_values_are_errors() {
+        assert!(load(&[("diff", "6")]).is_err());
+        assert!(load(&[("diff", "hard")]).is_err());
+        assert!(load(&[("created", "-1")]).is_err());
+    }
+
+    #[test]
+    fn created_only_gets_set_once() {
+        let mut info = LevelInfo::default();
+
+        info.changed(5);
+        info.changed(9);
+        assert_eq!((info.created, info.modified), (Some(5), Some(9)));
+    }
+}
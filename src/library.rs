@@ -0,0 +1,265 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::save::write_file_atomically;
+
+// Your own levels that you make in the editor
+// Every level is its own file in save-data/levels and the file name is the level's name
+
+pub const LEVELS_FOLDER: &str = "./save-data/levels";
+const MAX_NAME_LENGTH: usize = 24;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibraryLevel {
+    pub name: String,
+    pub object_count: usize,
+    pub platformer: bool,
+    pub modified: Option<SystemTime>
+}
+
+pub fn level_path(folder: &Path, name: &str) -> PathBuf {
+    folder.join(format!("{}.txt", name))
+}
+
+// Gets what the list shows without loading the whole level
+// Broken levels still show up so you can open them and see what's wrong or delete them
+fn summarize(name: String, text: &str, modified: Option<SystemTime>) -> LibraryLevel {
+    let (metadata, objects) = text.split_once(";;;").unwrap_or((text, ""));
+
+    LibraryLevel {
+        name,
+        object_count: objects.split(';').filter(|object| !object.is_empty()).count(),
+        platformer: metadata.split(';').any(|pair| pair == "mode:2"),
+        modified
+    }
+}
+
+// Newest levels go first
+pub fn list_levels(folder: &Path) -> Result<Vec<LibraryLevel>, String> {
+    fs::create_dir_all(folder).map_err(|error| format!("Failed to make {}: {}", folder.display(), error))?;
+
+    let entries = fs::read_dir(folder).map_err(|error| format!("Failed to read {}: {}", folder.display(), error))?;
+    let mut levels = vec![];
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = match path.file_name().and_then(|name| name.to_str()).and_then(|name| name.strip_suffix(".txt")) {
+            Some(name) => name.to_string(),
+            None => continue
+        };
+
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(error) => {
+                println!("Failed to read {}: {}", path.display(), error);
+                continue;
+            }
+        };
+        let modified = entry.metadata().and_then(|metadata| metadata.modified()).ok();
+
+        levels.push(summarize(name, &text, modified));
+    }
+
+    levels.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| a.name.cmp(&b.name)));
+    Ok(levels)
+}
+
+// Names become file names so they can only have letters, numbers, spaces, - and _
+pub fn check_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Err("The name can't be empty".to_string());
+    }
+
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(format!("The name can't be longer than {} letters", MAX_NAME_LENGTH));
+    }
+
+    if !name.chars().all(|character| character.is_ascii_alphanumeric() || character == ' ' || character == '-' || character == '_') {
+        return Err("The name can only have letters, numbers, spaces, - and _".to_string());
+    }
+
+    Ok(name.to_string())
+}
+
+// Adds a number on the end until no other level has the name
+fn unused_name(folder: &Path, name: &str) -> String {
+    let mut number = 1;
+    let mut new_name = name.to_string();
+
+    while level_path(folder, &new_name).exists() {
+        number += 1;
+        new_name = format!("{} {}", name, number);
+    }
+
+    new_name
+}
+
+// Gives back the name the level got
+pub fn create_level(folder: &Path, contents: &str) -> Result<String, String> {
+    let name = unused_name(folder, "New Level");
+
+    write_file_atomically(&level_path(folder, &name), contents)
+        .map_err(|error| format!("Failed to make the level: {}", error))?;
+
+    Ok(name)
+}
+
+pub fn duplicate_level(folder: &Path, name: &str) -> Result<String, String> {
+    let contents = fs::read_to_string(level_path(folder, name))
+        .map_err(|error| format!("Failed to read {}: {}", name, error))?;
+
+    // The copy can't end up with a name that's too long to rename it back
+    let base: String = format!("{} copy", name).chars().take(MAX_NAME_LENGTH - 3).collect();
+    let new_name = unused_name(folder, base.trim_end());
+
+    write_file_atomically(&level_path(folder, &new_name), &contents)
+        .map_err(|error| format!("Failed to copy {}: {}", name, error))?;
+
+    Ok(new_name)
+}
+
+// Gives back the new name after it gets cleaned up
+pub fn rename_level(folder: &Path, name: &str, new_name: &str) -> Result<String, String> {
+    let new_name = check_name(new_name)?;
+
+    if new_name == name {
+        return Ok(new_name);
+    }
+
+    // Only the capitals changing is fine on systems where that counts as the same file
+    // Everywhere else a level that only differs in capitals is a different level
+    let path = level_path(folder, name);
+    let new_path = level_path(folder, &new_name);
+    if new_path.exists() && !is_same_file(&path, &new_path) {
+        return Err(format!("There's already a level called {}", new_name));
+    }
+
+    fs::rename(path, new_path)
+        .map_err(|error| format!("Failed to rename {}: {}", name, error))?;
+
+    Ok(new_name)
+}
+
+#[cfg(unix)]
+fn is_same_file(path: &Path, other: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (fs::metadata(path), fs::metadata(other)) {
+        (Ok(metadata), Ok(other)) => metadata.dev() == other.dev() && metadata.ino() == other.ino(),
+        _ => false
+    }
+}
+
+// Canonicalizing gives back the real capitals of the file there
+#[cfg(not(unix))]
+fn is_same_file(path: &Path, other: &Path) -> bool {
+    match (fs::canonicalize(path), fs::canonicalize(other)) {
+        (Ok(path), Ok(other)) => path == other,
+        _ => false
+    }
+}
+
+pub fn delete_level(folder: &Path, name: &str) -> Result<(), String> {
+    fs::remove_file(level_path(folder, name)).map_err(|error| format!("Failed to delete {}: {}", name, error))
+}
+
+// How long ago a level got changed for the list
+pub fn time_ago(modified: Option<SystemTime>, now: SystemTime) -> String {
+    let modified = match modified {
+        Some(modified) => modified,
+        None => return String::new()
+    };
+    // Files from the future happen when the clock changes
+    let seconds = now.duration_since(modified).map(|time| time.as_secs()).unwrap_or(0);
+
+    let (amount, unit) = match seconds {
+        0..60 => return "Just now".to_string(),
+        60..3600 => (seconds / 60, "minute"),
+        3600..86400 => (seconds / 3600, "hour"),
+        _ => (seconds / 86400, "day")
+    };
+
+    format!("{} {}{} ago", amount, unit, if amount == 1 { "" } else { "s" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn temp_folder(name: &str) -> PathBuf {
+        let folder = std::env::temp_dir().join(format!("geometry-rays-library-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        folder
+    }
+
+    #[test]
+    fn levels_can_be_made_renamed_copied_and_deleted() {
+        let folder = temp_folder("levels");
+        let level = "version:1.6;mode:2;song:0;;;480:480:0:0:0:1;480:520:0:0:0:1";
+
+        assert_eq!(create_level(&folder, level), Ok("New Level".to_string()));
+        assert_eq!(create_level(&folder, level), Ok("New Level 2".to_string()));
+        assert_eq!(rename_level(&folder, "New Level", "  My Level "), Ok("My Level".to_string()));
+        assert!(rename_level(&folder, "My Level", "New Level 2").is_err());
+        assert!(rename_level(&folder, "My Level", "../outside").is_err());
+        assert_eq!(duplicate_level(&folder, "My Level"), Ok("My Level copy".to_string()));
+        assert_eq!(duplicate_level(&folder, "My Level"), Ok("My Level copy 2".to_string()));
+        delete_level(&folder, "New Level 2").unwrap();
+
+        let mut names: Vec<String> = list_levels(&folder).unwrap().into_iter().map(|level| level.name).collect();
+        names.sort();
+        assert_eq!(names, vec!["My Level", "My Level copy", "My Level copy 2"]);
+
+        let level = &list_levels(&folder).unwrap()[0];
+        assert_eq!((level.object_count, level.platformer), (2, true));
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn renaming_only_the_capitals() {
+        let folder = temp_folder("capitals");
+        let level = "version:1.7;mode:1;song:0;;;480:480:0:0:0:1:0";
+
+        assert_eq!(create_level(&folder, level), Ok("New Level".to_string()));
+        assert_eq!(rename_level(&folder, "New Level", "foo"), Ok("foo".to_string()));
+        assert_eq!(rename_level(&folder, "foo", "Foo"), Ok("Foo".to_string()));
+        assert!(level_path(&folder, "Foo").exists());
+
+        // Where foo and Foo can both be there renaming one can't write over the other
+        fs::write(level_path(&folder, "foo"), "other level").unwrap();
+        if !is_same_file(&level_path(&folder, "foo"), &level_path(&folder, "Foo")) {
+            assert!(rename_level(&folder, "foo", "Foo").is_err());
+            assert_eq!(fs::read_to_string(level_path(&folder, "Foo")).unwrap(), level);
+        }
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn names_are_checked() {
+        assert!(check_name("").is_err());
+        assert!(check_name("a/b").is_err());
+        assert!(check_name(".hidden").is_err());
+        assert!(check_name(&"a".repeat(MAX_NAME_LENGTH + 1)).is_err());
+        assert_eq!(check_name(" Level_1-b "), Ok("Level_1-b".to_string()));
+    }
+
+    #[test]
+    fn times_are_readable() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let ago = |seconds| time_ago(Some(now - Duration::from_secs(seconds)), now);
+
+        assert_eq!(ago(5), "Just now");
+        assert_eq!(ago(60), "1 minute ago");
+        assert_eq!(ago(7200), "2 hours ago");
+        assert_eq!(ago(86400 * 3), "3 days ago");
+        assert_eq!(time_ago(None, now), "");
+        assert_eq!(time_ago(Some(now + Duration::from_secs(60)), now), "Just now");
+    }
+}
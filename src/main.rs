@@ -12,6 +12,36 @@ use ez_reqwest::*;
 
 mod funcs;
 mod types;
+mod migrations;
+mod check;
+mod colors;
+mod verify;
+mod bench;
+mod autosave;
+mod account;
+mod history;
+mod inspector;
+mod level_info;
+mod library;
+mod objects;
+mod physics;
+mod player;
+mod replay;
+mod save;
+mod spatial;
+use physics::{PhysicsInput, PhysicsState, PhysicsEvent, FixedTimestep};
+use player::{GAMEMODES, PLAYER_COLORS};
+use account::{LoginResponse, LOGGED_IN_RESPONSE, is_server_down, is_token_rejected, parse_login_response};
+use autosave::{Autosave, AUTOSAVE_INTERVAL, EDITOR_AUTOSAVE_DELAY};
+use colors::{ColorChannels, ColorTarget, ColorTransition, DEFAULT_CHANNEL_COLOR, start_transition};
+use history::{EditHistory, EditKind};
+use inspector::inspect;
+use level_info::{LevelInfo, MAX_DIFFICULTY, from_unix_time, unix_time};
+use library::{LibraryLevel, LEVELS_FOLDER};
+use objects::{Behavior, ObjectCategory, ObjectRegistry, ObjectType};
+use replay::{Replay, ReplayOutcome};
+use save::{SaveData, SAVE_PATH, load_save, write_file_atomically, write_save};
+use spatial::ObjectColumns;
 use funcs::*;
 use types::*;
 
@@ -23,6 +53,20 @@ use MenuLogic::playing;
 
 #[tokio::main]
 async fn main() {
+    // Commands that run without opening a window
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(|arg| arg.as_str()) == Some("check") {
+        std::process::exit(check::run(&args[2..]));
+    }
+
+    if args.get(1).map(|arg| arg.as_str()) == Some("verify") {
+        std::process::exit(verify::run(&args[2..]));
+    }
+
+    if args.get(1).map(|arg| arg.as_str()) == Some("bench") {
+        std::process::exit(bench::run(&args[2..]));
+    }
+
     println!("Initializing raylib...");
     let (mut rl, thread) = raylib::init()
         .size(800, 600)
@@ -37,61 +81,24 @@ async fn main() {
     let logo_image = Image::load_image("Resources/logo.png").expect("Failed to load image");
     rl.set_window_icon(&logo_image);
 
-    println!("Loading object textures to be used in texture_ids");
-    let _null_texture = rl.load_texture(&thread, "Resources/null.png")
-        .expect("Failed to load null texture");
-    let spike_texture = rl.load_texture(&thread, "Resources/spike.png")
-        .expect("Failed to load spike texture");
-    let block_texture = rl.load_texture(&thread, "Resources/block.png")
-        .expect("Failed to load null texture");
-    let pad_texture = rl.load_texture(&thread, "Resources/pad.png")
-        .expect("Failed to load orb texture");
-    let orb_texture = rl.load_texture(&thread, "Resources/orb.png")
-        .expect("Failed to load orb texture");
-    let upside_down_portal_texture = rl.load_texture(&thread, "Resources/upside-down-portal.png")
-        .expect("Failed to load upside down portal texture");
-    let right_side_up_portal_texture = rl.load_texture(&thread, "Resources/right-side-up-portal.png")
-        .expect("Failed to load right side up portal texture");
-    let short_spike_texture = rl.load_texture(&thread, "Resources/short-spike.png")
-        .expect("Failed to load short spike texture");
-    let cube_portal_texture = rl.load_texture(&thread, "Resources/gamemode-portals/cube-portal.png")
-        .expect("Failed to load cube portal texture");
-    let ship_portal_texture = rl.load_texture(&thread, "Resources/gamemode-portals/ship-portal.png")
-        .expect("Failed to load ship portal texture");
-    let outline_block1 = rl.load_texture(&thread, "Resources/blocks/outline-block.png")
-        .expect("Failed to load outline block");
-    let outline_block2 = rl.load_texture(&thread, "Resources/blocks/outline-block-variant1.png")
-        .expect("Failed to load outline block variant 1");
-    let outline_block3 = rl.load_texture(&thread, "Resources/blocks/outline-block-variant2.png")
-        .expect("Failed to load outline block variant 2");
-    let outline_block4 = rl.load_texture(&thread, "Resources/blocks/outline-block-variant3.png")
-        .expect("Failed to load outline block variant 3");
-    let outline_block5 = rl.load_texture(&thread, "Resources/blocks/outline-block-variant4.png")
-        .expect("Failed to load outline block variant 4");
-    let end_trigger_texture = rl.load_texture(&thread, "Resources/end-trigger.png")
-        .expect("Failed to load end trigger texture");
-    let black_block_texture = rl.load_texture(&thread, "Resources/blocks/black-block.png")
-        .expect("Failed to load black block texture");
-    let half_speed_texture = rl.load_texture(&thread, "Resources/speed/05x.png")
-        .expect("Failed to load 0.5x speed portal texture");
-    let normal_speed_texture = rl.load_texture(&thread, "Resources/speed/1x.png")
-        .expect("Failed to load 1x speed portal texture");
-    let double_speed_texture = rl.load_texture(&thread, "Resources/speed/2x.png")
-        .expect("Failed to load 2x speed portal texture");
-    let triple_speed_texture = rl.load_texture(&thread, "Resources/speed/3x.png")
-        .expect("Failed to load 3x speed portal texture");
-    let gravity_pad_texture = rl.load_texture(&thread, "Resources/gravity-pad.png")
-        .expect("Failed to load gravity pad texture");
-    let gravity_orb_texture = rl.load_texture(&thread, "Resources/gravity-orb.png")
-        .expect("Failed to load gravity orb texture");
-    let color_trigger_texture = rl.load_texture(&thread, "Resources/color.png")
-        .expect("Failed to load color trigger texture");
-    let ball_portal_texture = rl.load_texture(&thread, "Resources/gamemode-portals/ball-portal.png")
-        .expect("Failed to load ball portal texture");
-    let wave_portal_texture = rl.load_texture(&thread, "Resources/gamemode-portals/wave-portal.png")
-        .expect("Failed to load wave portal texture");
-    let death_orb_texture = rl.load_texture(&thread, "Resources/death-orb.png")
-        .expect("Failed to load death orb texture");
+    println!("Loading object textures...");
+    // The game can't run without its own textures but custom objects with a broken texture just get skipped
+    let built_in_objects = ObjectRegistry::new().types.len();
+    let mut object_types: Vec<ObjectType> = vec![];
+    let mut object_textures: Vec<Texture2D> = vec![];
+    for (index, object_type) in ObjectRegistry::with_mods().types.into_iter().enumerate() {
+        match rl.load_texture(&thread, &object_type.texture) {
+            Ok(texture) => {
+                object_textures.push(texture);
+                object_types.push(object_type);
+            }
+            Err(error) if index >= built_in_objects => {
+                println!("Skipping custom object {}: Failed to load {}: {}", object_type.name, object_type.texture, error);
+            }
+            Err(error) => panic!("Failed to load {} texture: {}", object_type.name, error)
+        }
+    }
+    let object_registry = ObjectRegistry { types: object_types };
 
     // Create main menu buttons
     let mut play_button = Button::new(rl.get_screen_width() as f32 / 2.0 - 100.0, 250.0, 200.0, 50.0, "Play", 24, false);
@@ -99,6 +106,23 @@ async fn main() {
     let mut restart_button = Button::new(300.0, 320.0, 200.0, 50.0, "Restart", 24, false);
     let mut account_page_button = Button::new(rl.get_screen_width() as f32 - 220.0, 20.0, 200.0, 50.0, "Account Page", 24, false);
     let mut settings_button = Button::new(rl.get_screen_width() as f32 - 220.0, 90.0, 200.0, 50.0, "Settings", 24, false);
+    let mut customize_button = Button::new(rl.get_screen_width() as f32 - 220.0, 160.0, 200.0, 50.0, "Customize", 24, false);
+
+    // Buttons for picking which gamemode's icon is being changed on the customization screen
+    let mut customize_gamemode = GameMode::Cube;
+    let mut customize_gamemode_buttons: Vec<Button> = GAMEMODES
+        .iter()
+        .enumerate()
+        .map(|(index, gamemode)| Button::new(
+            210.0 + 100.0 * index as f32,
+            160.0,
+            80.0,
+            30.0,
+            &format!("{:?}", gamemode),
+            18,
+            false
+        ))
+        .collect();
 
     // Create online level buttons
     let mut menu_button = Button::new(20.0, 20.0, 200.0, 50.0, "Back to Menu", 24, false);
@@ -119,6 +143,7 @@ async fn main() {
         text_size: 30,
         max_length: 5,
         spaces_allowed: false,
+        symbols_allowed: false,
         active: false
     };
 
@@ -134,6 +159,50 @@ async fn main() {
     let mut level_upload_button = Button::new(675.0, 230.0, 100.0, 50.0, "Upload", 20, false);
 
     let mut level_options_back = Button::new(20.0, 20.0, 200.0, 50.0, "Back to Editor", 24, false);
+
+    // The level options have a second page for the name, description and such
+    let mut level_info_button = Button::new(260.0, rl.get_screen_height() as f32 - 70.0, 140.0, 50.0, "Level Info", 20, false);
+    let mut level_options_info = false;
+    let mut difficulty_prev_button = Button::new(460.0, 150.0, 40.0, 40.0, "<", 24, false);
+    let mut difficulty_next_button = Button::new(640.0, 150.0, 40.0, 40.0, ">", 24, false);
+    let mut level_info_textboxes: Vec<TextBox> = [
+        (120.0, 400.0, 20, "Level Name", false),
+        (205.0, 400.0, 20, "Author", false),
+        (315.0, 760.0, 50, "Level Description", false),
+        (475.0, 400.0, 30, "Song File", true)
+    ]
+        .iter()
+        .map(|(y, width, max_length, text, symbols_allowed)| TextBox {
+            rect: Rectangle { x: 20.0, y: *y, width: *width, height: 45.0 },
+            text: text.to_string(),
+            text_size: 25,
+            max_length: *max_length,
+            spaces_allowed: true,
+            symbols_allowed: *symbols_allowed,
+            active: false
+        })
+        .collect();
+
+    // Stuff for editing the level's color channels in the level options
+    let mut channel_editor_channel: u16 = 1;
+    let mut channel_prev_button = Button::new(20.0, 120.0, 40.0, 40.0, "<", 24, false);
+    let mut channel_next_button = Button::new(250.0, 120.0, 40.0, 40.0, ">", 24, false);
+    let mut channel_set_button = Button::new(20.0, 255.0, 125.0, 35.0, "Set", 20, false);
+    let mut channel_remove_button = Button::new(165.0, 255.0, 125.0, 35.0, "Remove", 20, false);
+    let mut channel_color_textboxes: Vec<TextBox> = [20.0, 115.0, 210.0]
+        .iter()
+        .map(|x| TextBox {
+            rect: Rectangle { x: *x, y: 210.0, width: 80.0, height: 35.0 },
+            text: "0".to_string(),
+            text_size: 20,
+            max_length: 3,
+            spaces_allowed: false,
+            symbols_allowed: false,
+            active: false
+        })
+        .collect();
+    let mut channel_color_text: Vec<String> = vec![String::new(); 3];
+    let mut channel_error: Option<String> = None;
     let red_bg_slider = Button::new(470.0, 100.0, 10.0, 150.0, "", 20, false);
     let green_bg_slider = Button::new(595.0, 100.0, 10.0, 150.0, "", 20, false);
     let blue_bg_slider = Button::new(720.0, 100.0, 10.0, 150.0, "", 20, false);
@@ -156,6 +225,7 @@ async fn main() {
         text_size: 30,
         max_length: 20,
         spaces_allowed: false,
+        symbols_allowed: false,
         active: false
     };
 
@@ -170,6 +240,7 @@ async fn main() {
         text_size: 30,
         max_length: 20,
         spaces_allowed: false,
+        symbols_allowed: false,
         active: false
     };
 
@@ -205,6 +276,7 @@ async fn main() {
         text_size: 30,
         max_length: 20,
         spaces_allowed: true,
+        symbols_allowed: false,
         active: false
     };
 
@@ -219,6 +291,7 @@ async fn main() {
         text_size: 30,
         max_length: 50,
         spaces_allowed: true,
+        symbols_allowed: false,
         active: false
     };
 
@@ -264,15 +337,22 @@ async fn main() {
         false
     );
 
-    let mut clear_level_button = Button::new(
-        rl.get_screen_width() as f32 - 120.0,
-        rl.get_screen_height() as f32 - 120.0,
-        100.0,
-        100.0,
-        "Clear Level",
-        18,
-        false
-    );
+    // Buttons for the list of your own levels
+    let mut library_new_button = Button::new(rl.get_screen_width() as f32 - 220.0, 20.0, 200.0, 50.0, "New Level", 24, false);
+    let mut library_rename_button = Button::new(250.0, 480.0, 110.0, 45.0, "Rename", 20, true);
+    let mut library_open_button = Button::new(370.0, 480.0, 120.0, 45.0, "Open", 20, true);
+    let mut library_duplicate_button = Button::new(500.0, 480.0, 130.0, 45.0, "Duplicate", 20, true);
+    let mut library_delete_button = Button::new(640.0, 480.0, 140.0, 45.0, "Delete", 20, true);
+
+    let mut library_rename_textbox = TextBox {
+        rect: Rectangle { x: 20.0, y: 480.0, width: 220.0, height: 45.0 },
+        text: "Level Name".to_string(),
+        text_size: 20,
+        max_length: 24,
+        spaces_allowed: true,
+        symbols_allowed: true,
+        active: false
+    };
 
     let mut no_touch_toggle = Button::new(
         rl.get_screen_width() as f32 - 95.0,
@@ -304,98 +384,6 @@ async fn main() {
         true
     );
 
-    let mut set_color_red = Button::new(
-        rl.get_screen_width() as f32 / 1.4,
-        120.0,
-        100.0,
-        100.0,
-        "Set",
-        20,
-        false
-    );
-
-    let mut set_color_green = Button::new(
-        rl.get_screen_width() as f32 / 1.4,
-        240.0,
-        100.0,
-        100.0,
-        "Set",
-        20,
-        false
-    );
-
-    let mut set_color_blue = Button::new(
-        rl.get_screen_width() as f32 / 1.4,
-        360.0,
-        100.0,
-        100.0,
-        "Set",
-        20,
-        false
-    );
-
-    let mut color_red_textbox = TextBox {
-        rect: Rectangle {
-            x: rl.get_screen_width() as f32 / 1.4 - 140.0,
-            y: 120.0 + 50.0 / 2.0,
-            width: 110.0,
-            height: 50.0
-        },
-        text: "Red".to_string(),
-        text_size: 30,
-        max_length: 3,
-        spaces_allowed: false,
-        active: false
-    };
-
-    let mut color_green_textbox = TextBox {
-        rect: Rectangle {
-            x: rl.get_screen_width() as f32 / 1.4 - 140.0,
-            y: 240.0 + 50.0 / 2.0,
-            width: 110.0,
-            height: 50.0
-        },
-        text: "Green".to_string(),
-        text_size: 30,
-        max_length: 3,
-        spaces_allowed: false,
-        active: false
-    };
-
-    let mut color_blue_textbox = TextBox {
-        rect: Rectangle {
-            x: rl.get_screen_width() as f32 / 1.4 - 140.0,
-            y: 360.0 + 50.0 / 2.0,
-            width: 110.0,
-            height: 50.0
-        },
-        text: "Blue".to_string(),
-        text_size: 30,
-        max_length: 3,
-        spaces_allowed: false,
-        active: false
-    };
-
-    let mut set_color_type_bg = Button::new(
-        120.0,
-        rl.get_screen_height() as f32 / 1.6,
-        100.0,
-        100.0,
-        "Background",
-        15,
-        false
-    );
-
-    let mut set_color_type_grnd = Button::new(
-        240.0,
-        rl.get_screen_height() as f32 / 1.6,
-        100.0,
-        100.0,
-        "Ground",
-        20,
-        true
-    );
-
     let mut set_level_type_normal = Button::new(
         20.0,
         rl.get_screen_height() as f32 - 120.0,
@@ -436,6 +424,16 @@ async fn main() {
         true
     );
 
+    let mut smooth_movement_toggle = Button::new(
+        rl.get_screen_width() as f32 / 2.0 - 65.0,
+        440.0,
+        130.0,
+        130.0,
+        "Smooth movement",
+        15,
+        true
+    );
+
     // Url's for server requests
     let main_url = "http://georays.puppet57.xyz/php-code/".to_string();
     let latest_version_url: String = format!("{}get-latest-version.php", main_url).to_string();
@@ -448,31 +446,29 @@ async fn main() {
     println!("Getting random stuff ready...");
     let mut game_state = GameState::Menu;
     let mut active_popup: ActivePopup = ActivePopup::None;
-    let mut player = Rectangle::new(200.0, 500.0, 40.0, 40.0);
-    let mut centered_player = Rectangle::new(player.x + player.width / 2.0, player.y + player.height / 2.0, player.width, player.height);
-    let mut small_player = player;
-    let mut is_on_ground = true;
-    let mut world_offset = 0.0;
-    let mut rotation = 0.0;
     let mut attempt = 1;
-    let mut on_orb: bool = false;
-    let mut kill_player: bool = false;
-    let mut texture_ids: Vec<&Texture2D> = vec![&_null_texture];
-    let mut current_gamemode = GameMode::Cube;
-    let mut player_cam_y: i32 = 0;
-    let mut touching_block_ceiling: bool = false;
 
     println!("Getting physics ready...");
-    let mut velocity_y = 0.0;
-    let mut gravity = 0.8;
-    let default_gravity = gravity;
-    let mut jump_force = -13.0;
-    let default_jump_force = jump_force;
-    let mut movement_speed = 6.0;
-    let default_movement_speed = movement_speed;
-    let ship_power: f32 = 0.7;
-    let ship_falling_speed: f32 = 0.5;
-    let wave_velocity: f32 = 1.1;
+    let mut physics = PhysicsState::new();
+    let mut previous_physics = physics.clone();
+    let mut timestep = FixedTimestep::new();
+    // Letting go has to wait for the next tick when a frame doesn't run any
+    let mut jump_released = false;
+    // This gets rebuilt when a level starts and when the level changes in the editor
+    let mut object_columns = ObjectColumns::new(&[]);
+    let mut object_columns_revision: Option<u64> = None;
+
+    // Autosaving
+    // The save file gets written whenever your progress changes
+    // The level you are editing gets written a bit after you stop changing it and every so often
+    let quit_requested = autosave::quit_signal();
+    let mut editor_autosave = Autosave::new(EDITOR_AUTOSAVE_DELAY, AUTOSAVE_INTERVAL);
+    let mut editor_autosave_revision: Option<u64> = None;
+    let mut saved_save_data: Option<SaveData> = None;
+    let mut replay_recording = Replay::new("1", &[], 0.0);
+    let mut replay_playback: Option<Replay> = None;
+    // The last attempt that died only gets written if you press P
+    let mut last_attempt: Option<Replay> = None;
 
     println!("Getting even more variables ready...");
     let version = "1.61";
@@ -527,30 +523,19 @@ async fn main() {
     ];
     let mut current_level = 0;
     let mut reset_menu_music = false;
-    let mut stars: u32 = 0;
-    let save_data = fs::read_to_string("./save-data/save.txt")
-        .expect("Failed to read save file");
     let mut in_custom_level: bool = false;
-    let mut levels_completed_vec: Vec<bool> = vec![
-        false,
-        false,
-        false,
-        false,
-        false
-    ];
     let mut logged_in: bool = false;
-    let mut online_levels_beaten: Vec<u16> = vec![];
     let mut is_mod: bool = false;
-    let default_level: &str = "version:1.6;mode:1;song:0;c1001:0,0,50;c1002:0,0,100;c1004:255,255,255;bg:1;grnd:1;;;480:480:0:0:0:1";
+    let default_level: &str = "version:1.7;mode:1;song:0;c1001:0,0,50;c1002:0,0,100;c1004:255,255,255;bg:1;grnd:1;;;480:480:0:0:0:1:0";
     let mut start_pos: u16 = 0;
     let in_debug_build = cfg!(debug_assertions);
     let mut cached_levels: HashMap<String, String> = HashMap::new();
     let mut current_mode: String = "1".to_string();
-    let mut moving_direction: u8 = 0;
     let mut bg_offset: f32 = 0.0;
     let mut grnd_offset: f32 = 0.0;
     let mut options: Vec<bool> = vec![
         true,
+        false,
         false
     ];
 
@@ -559,6 +544,7 @@ async fn main() {
     let mut register_result = "".to_string();
     let mut login_result = "".to_string();
     let mut level_upload_result = "".to_string();
+    let mut level_verify_result = "".to_string();
     let mut level_download_result = "".to_string();
     let mut online_level_name = "".to_string();
     let mut online_level_desc = "".to_string();
@@ -572,223 +558,74 @@ async fn main() {
     let mut level_rate_result = "".to_string();
     let mut show_server_down = false;
 
-    texture_ids.push(&spike_texture);
-    texture_ids.push(&block_texture);
-    texture_ids.push(&pad_texture);
-    texture_ids.push(&orb_texture);
-    texture_ids.push(&upside_down_portal_texture);
-    texture_ids.push(&right_side_up_portal_texture);
-    texture_ids.push(&short_spike_texture);
-    texture_ids.push(&cube_portal_texture);
-    texture_ids.push(&ship_portal_texture);
-    texture_ids.push(&outline_block1);
-    texture_ids.push(&outline_block2);
-    texture_ids.push(&outline_block3);
-    texture_ids.push(&outline_block4);
-    texture_ids.push(&outline_block5);
-    texture_ids.push(&end_trigger_texture);
-    texture_ids.push(&black_block_texture);
-    texture_ids.push(&normal_speed_texture);
-    texture_ids.push(&double_speed_texture);
-    texture_ids.push(&triple_speed_texture);
-    texture_ids.push(&half_speed_texture);
-    texture_ids.push(&gravity_pad_texture);
-    texture_ids.push(&gravity_orb_texture);
-    texture_ids.push(&color_trigger_texture);
-    texture_ids.push(&ball_portal_texture);
-    texture_ids.push(&wave_portal_texture);
-    texture_ids.push(&death_orb_texture);
-
     println!("Getting the editor ready...");
     let mut active_tab = EditorTab::Build;
     let mut edit_not_done_yet = false;
-    let mut objects: Vec<&str> = vec!["null"];
     let mut current_object = 1;
-    let mut _advanced_page_number = 0;
     let mut cam_pos_x = 0;
     let mut cam_pos_y = 0;
     let mut object_grid: Vec<ObjectStruct> = vec![];
+    let mut edit_history = EditHistory::new();
+    let mut clipboard: Vec<ObjectStruct> = vec![];
+    let mut selection_start: Option<Vector2> = None;
     let grid_size = 40;
     let mut red_bg_slider_pos: u8 = 75;
     let mut green_bg_slider_pos: u8 = 75;
     let mut blue_bg_slider_pos: u8 = 125;
-    let mut level_string = fs::read_to_string("./save-data/levels/level.txt")
-        .expect("Failed to load level file");
-    let mut parts: Vec<&str> = level_string.split(";;;").collect();
-    let mut _level_metadata = parts[0];
-    let mut _object_string = parts[1];
+    // The level that's open in the editor
+    // The editor saves to whatever level you opened from your levels
+    let levels_folder = std::path::Path::new(LEVELS_FOLDER);
+    let mut open_level_path = library::level_path(levels_folder, "level");
+    let mut level_string = String::new();
+    let mut level_info = LevelInfo::default();
+    let mut library_levels: Vec<LibraryLevel> = vec![];
+    let mut library_selected: Option<String> = None;
+    let mut library_scroll: usize = 0;
+    let mut library_rename_text = String::new();
+    let mut library_message = String::new();
+    let mut library_delete_confirm = false;
+    let mut library_needs_refresh = true;
     let mut current_song: u8 = 0;
     let mut song_selected: bool = false;
     let mut from_editor: bool = false;
     let mut player_path: Vec<Vector2> = vec![];
     let mut editor_guide_scroll: u16 = 0;
-    let mut touching_color_trigger = false;
-    let mut selected_object: u16 = 1;
+    let mut inspector: Vec<InspectorRow> = vec![];
+    let mut inspector_error: Option<String> = None;
+    let mut level_load_error: Option<LevelParseError> = None;
+    let mut replay_error: Option<String> = None;
 
     let mut red_ground_slider_pos: i32 = 355;
     let mut green_ground_slider_pos: i32  = 355;
     let mut blue_ground_slider_pos: i32 = 455;
 
-    objects.push("spike");
-    objects.push("block");
-    objects.push("pad");
-    objects.push("orb");
-    objects.push("upside down");
-    objects.push("right side up");
-    objects.push("short spike");
-    objects.push("cube portal");
-    objects.push("ship portal");
-    objects.push("outline block");
-    objects.push("outline block 2");
-    objects.push("outline block 3");
-    objects.push("outline block 4");
-    objects.push("outline block 5");
-    objects.push("end trigger");
-    objects.push("black block");
-    objects.push("1x speed");
-    objects.push("2x speed");
-    objects.push("3x speed");
-    objects.push("0.5x speed");
-    objects.push("gravity pad");
-    objects.push("gravity orb");
-    objects.push("color trigger");
-    objects.push("ball portal");
-    objects.push("wave portal");
-    objects.push("death orb");
-
     // The buttons used for selecting what object to place
-    let obj_button_off = 65.0;
-    let mut obj_btns_vec: Vec<ObjButton> = vec![
-        ObjButton {
-            btn: Button::new(187.0, 415.0, 50.0, 50.0, objects.get(1).unwrap(), 10, false),
-            obj_id: 1
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off), 415.0, 50.0, 50.0, objects.get(2).unwrap(), 10, false),
-            obj_id: 2
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 2.0), 415.0, 50.0, 50.0, objects.get(3).unwrap(), 10, false),
-            obj_id: 3
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 3.0), 415.0, 50.0, 50.0, objects.get(4).unwrap(), 10, false),
-            obj_id: 4
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 4.0), 415.0, 50.0, 50.0, objects.get(5).unwrap(), 10, false),
-            obj_id: 5
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 5.0), 415.0, 50.0, 50.0, objects.get(6).unwrap(), 10, false),
-            obj_id: 6
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 6.0), 415.0, 50.0, 50.0, objects.get(7).unwrap(), 10, false),
-            obj_id: 7
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 7.0), 415.0, 50.0, 50.0, objects.get(8).unwrap(), 10, false),
-            obj_id: 8
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 8.0), 415.0, 50.0, 50.0, objects.get(9).unwrap(), 10, false),
-            obj_id: 9
-        },
-
-        ObjButton {
-            btn: Button::new(187.0, 415.0 + obj_button_off, 50.0, 50.0, objects.get(10).unwrap(), 10, false),
-            obj_id: 10
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + obj_button_off, 415.0 + obj_button_off, 50.0, 50.0, objects.get(11).unwrap(), 10, false),
-            obj_id: 11
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 2.0), 415.0 + obj_button_off, 50.0, 50.0, objects.get(12).unwrap(), 10, false),
-            obj_id: 12
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 3.0), 415.0 + obj_button_off, 50.0, 50.0, objects.get(13).unwrap(), 10, false),
-            obj_id: 13
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 4.0), 415.0 + obj_button_off, 50.0, 50.0, objects.get(14).unwrap(), 10, false),
-            obj_id: 14
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 5.0), 415.0 + obj_button_off, 50.0, 50.0, objects.get(15).unwrap(), 10, false),
-            obj_id: 15
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 6.0), 415.0 + obj_button_off, 50.0, 50.0, objects.get(16).unwrap(), 10, false),
-            obj_id: 16
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 7.0), 415.0 + obj_button_off, 50.0, 50.0, objects.get(17).unwrap(), 10, false),
-            obj_id: 17
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 8.0), 415.0 + obj_button_off, 50.0, 50.0, objects.get(18).unwrap(), 10, false),
-            obj_id: 18
-        },
-
-        ObjButton {
-            btn: Button::new(187.0, 415.0 + (obj_button_off * 2.0), 50.0, 50.0, objects.get(19).unwrap(), 10, false),
-            obj_id: 19
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + obj_button_off, 415.0 + (obj_button_off * 2.0), 50.0, 50.0, objects.get(20).unwrap(), 10, false),
-            obj_id: 20
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 2.0), 415.0 + (obj_button_off * 2.0), 50.0, 50.0, objects.get(21).unwrap(), 10, false),
-            obj_id: 21
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 3.0), 415.0 + (obj_button_off * 2.0), 50.0, 50.0, objects.get(22).unwrap(), 10, false),
-            obj_id: 22
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 4.0), 415.0 + (obj_button_off * 2.0), 50.0, 50.0, objects.get(23).unwrap(), 10, false),
-            obj_id: 23
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 5.0), 415.0 + (obj_button_off * 2.0), 50.0, 50.0, objects.get(24).unwrap(), 10, false),
-            obj_id: 24
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 6.0), 415.0 + (obj_button_off * 2.0), 50.0, 50.0, objects.get(25).unwrap(), 10, false),
-            obj_id: 25
-        },
-
-        ObjButton {
-            btn: Button::new(187.0 + (obj_button_off * 7.0), 415.0 + (obj_button_off * 2.0), 50.0, 50.0, objects.get(26).unwrap(), 10, false),
-            obj_id: 26
+    // They get remade whenever the tab or the search changes
+    let mut object_category = ObjectCategory::Blocks;
+    let mut object_search = String::new();
+    let mut obj_btns_vec: Vec<ObjButton> = object_buttons(&object_registry, object_category, &object_search);
+    let mut object_page_count = obj_btns_vec.last().map(|obj_btn| obj_btn.page + 1).unwrap_or(1);
+    let mut object_page: usize = 0;
+    let mut previous_object_page = Button::new(765.0, 437.0, 30.0, 60.0, "<", 20, false);
+    let mut next_object_page = Button::new(765.0, 525.0, 30.0, 60.0, ">", 20, false);
+    let mut category_buttons: Vec<(ObjectCategory, Button)> = ObjectCategory::ALL.iter()
+        .enumerate()
+        .map(|(index, category)| (*category, Button::new(187.0 + 75.0 * index as f32, 405.0, 70.0, 25.0, category.name(), 12, false)))
+        .collect();
+    let mut object_search_textbox = TextBox {
+        rect: Rectangle {
+            x: 640.0,
+            y: 405.0,
+            width: 155.0,
+            height: 25.0
         },
-    ];
+        text: "Search".to_string(),
+        text_size: 15,
+        max_length: 20,
+        spaces_allowed: true,
+        symbols_allowed: false,
+        active: false
+    };
 
     let mut bg_red = red_bg_slider_pos - 75;
     let mut bg_green = green_bg_slider_pos - 75;
@@ -798,6 +635,14 @@ async fn main() {
     let mut ground_green = green_ground_slider_pos - 355;
     let mut ground_blue = blue_ground_slider_pos - 355;
 
+    // Color triggers that are still fading
+    let mut color_transitions: Vec<ColorTransition> = vec![];
+
+    // The level's own color channels and the ones being used while playing
+    // Triggers change the playing ones so every attempt starts with the level's colors
+    let mut level_color_channels = ColorChannels::new();
+    let mut color_channels = ColorChannels::new();
+
     // Color Channels
     // CC stands for Color Channel
     // 1001 is the bg
@@ -805,10 +650,11 @@ async fn main() {
     // 1003 is the player
     // 1004 is used by spikes and eventually blocks by default so basically obj color in gd
     // Everything before 1001 is just like in gd where you can use them for whatever you want
-    // But custom color channels dont exist yet
+    // Those ones live in level_color_channels and objects that don't use one get cc_1004
     let mut cc_1001 = Color { r:bg_red, g:bg_green, b:bg_blue, a:255 };
     let mut cc_1002 = Color { r:ground_red as u8, g:ground_green as u8, b:ground_blue as u8, a:255 };
-    let mut cc_1003 = Color::LIME;
+    // The player color comes from your save file so it gets set once the save is loaded
+    let mut cc_1003: Color;
     let cc_1004 = Color::WHITE;
 
     println!("Loading textures...");
@@ -840,6 +686,34 @@ async fn main() {
         rl.load_texture(&thread, "./Resources/difficulties/10.png").expect("Failed to load difficulty face"),
     ];
 
+    println!("Loading player icons...");
+    let icon_files = player::icon_files();
+    let mut player_icons: Vec<Vec<PlayerIcon>> = vec![];
+    for gamemode in GAMEMODES {
+        let mut icons = vec![];
+
+        for icon in player::find_icons(&icon_files, gamemode) {
+            let texture = match rl.load_texture(&thread, &player::icon_path(gamemode, icon)) {
+                Ok(texture) => texture,
+                Err(error) => {
+                    println!("Failed to load player icon: {}", error);
+                    continue;
+                }
+            };
+
+            let secondary_path = player::secondary_icon_path(gamemode, icon);
+            let secondary = if std::path::Path::new(&secondary_path).exists() {
+                rl.load_texture(&thread, &secondary_path).ok()
+            } else {
+                None
+            };
+
+            icons.push(PlayerIcon { icon, texture, secondary });
+        }
+
+        player_icons.push(icons);
+    }
+
     println!("Loading audio files...");
     let menu_loop_file = BufReader::new(File::open("Resources/menu-loop.mp3").expect("Failed to open MP3 file"));
     let menu_loop = Decoder::new(menu_loop_file).expect("Failed to decode MP3 file").repeat_infinite();
@@ -858,65 +732,40 @@ async fn main() {
     );
 
     println!("Loading save data...");
-    let values_levels: Vec<&str> = save_data.split(";;;").collect();
-    let save_pairs: Vec<&str> = values_levels[0].split(";").collect();
-    let levels_completed: Vec<&str> = values_levels[1].split(";").collect();
-    let online_levels_completed: Vec<&str> = values_levels[2].split(";").collect();
-    let mut user = "0".to_string();
-    let mut pass = "0".to_string();
-    for pair in save_pairs {
-        let key_value: Vec<&str> = pair.split(":").collect();
-
-        if key_value[0] == "stars" {
-            stars = key_value[1].parse::<u32>().unwrap();
-        }
+    let mut save_data: SaveData = load_save(std::path::Path::new(SAVE_PATH));
 
-        if key_value[0] == "user" {
-            if key_value[1] != "0" {
-                user = key_value[1].to_string();
-            }
-        }
+    // Older saves might not have every main level in them
+    save_data.main_levels_completed.resize(main_levels.len(), false);
 
-        if key_value[0] == "pass" {
-            if key_value[1] != "0" {
-                pass = key_value[1].to_string();
-            }
-        }
-    }
-
-    // This is for checking what main levels you have completed
-    let mut level_index: u8 = 0;
-    for level in levels_completed {
-        let key_value: Vec<&str> = level.split(":").collect();
-        if key_value[1] == "1" {
-            levels_completed_vec[level_index as usize] = true
-        }
-
-        level_index += 1;
-    }
-
-    // This is for checking what online levels you have completed
-    for level in online_levels_completed {
-        online_levels_beaten.push(level.parse().unwrap());
-    }
+    cc_1003 = rgb_color(save_data.player.primary);
 
     // This is for auto login
     // Auto login only runs if you have already logged in using the login page
-    if user != "0" && pass != "0" {
+    // If the server doesn't take your token anymore the login page gets shown so you can log in again
+    if let Some(account::Session { user, token: Some(token) }) = save_data.session.clone() {
         println!("Logging in...");
-        login_result = post_request(
+        let response = post_request(
             login_url.clone(),
             Some(hashmap! {
                 "user".to_string() => user.clone(),
-                "pass".to_string() => pass.clone()
+                "token".to_string() => token.clone()
             })
         ).await;
 
-        if login_result == "Logged in!" {
-            logged_in = true;
-            if user == "Puppet" {
-                is_mod = true
+        match parse_login_response(&user, &response, Some(&token)) {
+            LoginResponse::LoggedIn(session) => {
+                logged_in = true;
+                is_mod = session.is_mod();
+                login_result = LOGGED_IN_RESPONSE.to_string();
+                save_data.session = Some(session);
+            }
+            LoginResponse::TokenRejected => {
+                save_data.session = None;
+                login_result = "Please log in again!".to_string();
+                game_state = GameState::AccountPage;
             }
+            // The server being down doesn't mean your token is bad so it gets kept
+            LoginResponse::Failed(problem) => login_result = problem
         }
     }
 
@@ -929,14 +778,14 @@ async fn main() {
 
     let mut level_id: String = "".to_string();
 
-    let mut color_red_text: String = "".to_string();
-    let mut color_green_text: String = "".to_string();
-    let mut color_blue_text: String = "".to_string();
 
     // Main game loop
-    while !rl.window_should_close() {
+    // Closing the window and being told to quit by the system both end up saving after the loop
+    while !rl.window_should_close() && !quit_requested.load(std::sync::atomic::Ordering::SeqCst) {
         // All of these are variables that are set every frame
         // I don't really put variables here anymore
+        let was_editing = (game_state == GameState::Editor || game_state == GameState::LevelOptions)
+            && level_load_error.is_none();
         let space_down = rl.is_key_down(KeyboardKey::KEY_SPACE);
         let mouse_down = rl.is_mouse_button_down(MouseButton::MOUSE_BUTTON_LEFT);
         let delta_time = rl.get_frame_time();
@@ -1026,38 +875,15 @@ async fn main() {
                     game_state = GameState::OptionsMenu
                 }
 
+                if customize_button.is_clicked(&rl) {
+                    game_state = GameState::PlayerCustomization
+                }
+
                 account_page_button.update(&rl, delta_time);
                 settings_button.update(&rl, delta_time);
+                customize_button.update(&rl, delta_time);
             }
             GameState::Playing => {
-                if kill_player == true {
-                    kill_player = false;
-                }
-
-                // This calls the function that handles physics
-                // You can find the function in src/MenuLogic/playing.rs
-                playing::physics_handle(
-                    &mut player,
-                    current_gamemode,
-                    &mut is_on_ground,
-                    space_down,
-                    mouse_down,
-                    &mut velocity_y,
-                    jump_force,
-                    &mut gravity,
-                    touching_block_ceiling,
-                    ship_power,
-                    ship_falling_speed,
-                    wave_velocity,
-                    current_mode.clone(),
-                    &mut world_offset,
-                    movement_speed,
-                    &mut moving_direction,
-                    &mut rotation,
-                    &mut player_cam_y,
-                    &rl
-                );
-
                 // for obstacle in &obstacles {
                 //     let actual_x = obstacle.x + world_offset;
                 //     if check_collision_triangle_rectangle(
@@ -1079,107 +905,145 @@ async fn main() {
                 //     }
                 // }
 
-                // Updating the centered player hitbox
-                centered_player = Rectangle::new(player.x - player.width / 2.0, player.y - player.height / 2.0, player.width, player.height);
-
-                // Updating the secondary player hitbox used for blocks
-                small_player = centered_player;
-                small_player.x = centered_player.x + 15.0;
-                small_player.y = centered_player.y + 10.0;
-                small_player.width = 20.0;
-                small_player.height = 20.0;
-
-                // This is for checking if the player is touching an object
-                for object in &object_grid {
-                    if object.x as f32 + world_offset < rl.get_screen_width() as f32 &&
-                    object.x as f32 + world_offset > -40.0 && object.no_touch == 0 {
-                        // The amount of arguments in this function makes me want to kill myself
-                        // Anyways this function handles object collision
-                        playing::hitbox_collision(
-                            object,
-                            &mut player,
-                            centered_player,
-                            small_player,
-                            &mut velocity_y,
-                            &mut movement_speed,
-                            default_movement_speed,
-                            &mut gravity,
-                            default_gravity,
-                            &mut rotation,
-                            &mut jump_force,
-                            default_jump_force,
-                            &mut cc_1003,
-                            &mut kill_player,
-                            &mut is_on_ground,
-                            &mut on_orb,
-                            &mut touching_block_ceiling,
-                            &mut world_offset,
-                            player_cam_y,
-                            &mut current_gamemode,
-                            current_mode.clone(),
-                            mouse_down,
-                            space_down,
-                            &mut touching_color_trigger,
-                            &mut bg_red,
-                            &mut bg_green,
-                            &mut bg_blue,
-                            &mut ground_red,
-                            &mut ground_green,
-                            &mut ground_blue,
-                            &mut game_state,
-                            in_custom_level,
-                            &mut stars,
-                            &main_levels,
-                            current_level,
-                            &mut levels_completed_vec,
-                            &mut online_levels_beaten,
-                            level_id.clone(),
-                            online_level_rated,
-                            online_level_diff
+                // Physics run at a fixed 60 ticks a second so the game plays the same on any frame rate
+                // This can run more than once or not at all in a frame
+                jump_released |= rl.is_mouse_button_released(MouseButton::MOUSE_BUTTON_LEFT)
+                    || rl.is_key_released(KeyboardKey::KEY_SPACE);
+                let mut physics_input = PhysicsInput {
+                    jump: space_down || mouse_down,
+                    left: rl.is_key_down(KeyboardKey::KEY_LEFT),
+                    right: rl.is_key_down(KeyboardKey::KEY_RIGHT),
+                    mouse: mouse_down,
+                    released: jump_released
+                };
+                let ticks = timestep.advance(delta_time);
+
+                let mut tick = 0;
+                while tick < ticks && game_state == GameState::Playing {
+                    previous_physics = physics.clone();
+
+                    // Every tick gets recorded so the attempt can be saved as a replay
+                    // When watching a replay the inputs come from it instead of the keyboard
+                    let tick_input = match &replay_playback {
+                        Some(replay) => replay.input(replay_recording.inputs.len()),
+                        None => physics_input
+                    };
+                    replay_recording.inputs.push(tick_input);
+                    physics_input.released = false;
+                    jump_released = false;
+
+                    // This calls the function that handles physics and object collision
+                    // You can find it in src/physics.rs
+                    physics::step(&mut physics, tick_input, &object_grid, &object_columns, &current_mode, &object_registry);
+
+                    // This handles the things objects did that the physics can't do by itself
+                    for event in &physics.events {
+                        match event {
+                            PhysicsEvent::ColorTrigger { red, green, blue, target, fade_time, easing } => {
+                                let current_color = match target {
+                                    ColorTarget::Background => (bg_red, bg_green, bg_blue),
+                                    ColorTarget::Ground => (ground_red as u8, ground_green as u8, ground_blue as u8),
+                                    ColorTarget::Channel(channel) => color_channels.get(channel).copied().unwrap_or(DEFAULT_CHANNEL_COLOR)
+                                };
+
+                                start_transition(
+                                    &mut color_transitions,
+                                    ColorTransition::new(*target, current_color, (*red, *green, *blue), *fade_time, *easing)
+                                );
+                            }
+                            PhysicsEvent::LevelComplete => {
+                                // Beating a level saves the run as the replay for that level
+                                // It only gets saved if playing it back beats the level too
+                                if replay_playback.is_none()
+                                && matches!(replay_recording.play(&object_grid, &object_registry), ReplayOutcome::Completed { .. }) {
+                                    if let Err(error) = replay_recording.save(&replay::replay_path(replay_recording.level_hash)) {
+                                        println!("Failed to save replay: {}", error);
+                                    }
+                                }
+
+                                playing::complete_level(
+                                    &mut game_state,
+                                    in_custom_level,
+                                    &mut save_data,
+                                    &main_levels,
+                                    current_level,
+                                    level_id.clone(),
+                                    online_level_rated,
+                                    online_level_diff
+                                );
+                            }
+                        }
+                    }
+
+                    // This adds points to the player path
+                    // This only happens when playtesting a level in the editor or while in wave
+                    if from_editor || physics.gamemode == GameMode::Wave {
+                        player_path.push(
+                            Vector2 {
+                                x: 200.0 - physics.world_offset,
+                                y: physics.player.y + physics.player_cam_y as f32
+                            }
                         );
                     }
-                }
 
-                // This adds points to the player path
-                // This only happens when playtesting a level in the editor or while in wave
-                if from_editor || current_gamemode == GameMode::Wave {
-                    player_path.push(
-                        Vector2 {
-                            x: 200.0 - world_offset,
-                            y: player.y + player_cam_y as f32
+                    // Triggers with no fade time finish on the first tick so they still change the color instantly
+                    for transition in &mut color_transitions {
+                        let (red, green, blue) = transition.tick();
+
+                        match transition.target {
+                            ColorTarget::Background => {
+                                bg_red = red;
+                                bg_green = green;
+                                bg_blue = blue;
+                            }
+                            ColorTarget::Ground => {
+                                ground_red = red as i32;
+                                ground_green = green as i32;
+                                ground_blue = blue as i32;
+                            }
+                            ColorTarget::Channel(channel) => {
+                                color_channels.insert(channel, (red, green, blue));
+                            }
                         }
-                    );
-                }
+                    }
+                    color_transitions.retain(|transition| !transition.is_finished());
 
-                // This just makes it so if the player is dead then it goes to the game over screen
-                if kill_player {
-                    attempt += 1;
-                    if from_editor {
-                        sink.stop();
-                        sink.append(menu_loop.clone());
-                        sink.play();
-                        game_state = GameState::Editor
-                    } else {
-                        if options[1] {
-                            game_state = GameState::GameOver
+                    // This just makes it so if the player is dead then it goes to the game over screen
+                    if physics.kill_player {
+                        attempt += 1;
+                        color_transitions.clear();
+                        color_channels = level_color_channels.clone();
+
+                        // The last attempt is kept around so it can be saved for reproducing bugs
+                        if replay_playback.is_none() {
+                            last_attempt = Some(replay_recording.clone());
+                        }
+
+                        if from_editor {
+                            sink.stop();
+                            sink.append(menu_loop.clone());
+                            sink.play();
+                            game_state = GameState::Editor
                         } else {
-                            player.y = 500.0;
-                            world_offset = 0.0;
-                            rotation = 0.0;
-                            gravity = default_gravity;
-                            jump_force = default_jump_force;
-                            current_gamemode = GameMode::Cube;
-                            cc_1003 = Color::LIME;
-                            in_custom_level = false;
-                            velocity_y = 0.0;
-                            player_cam_y = 0;
-                            movement_speed = default_movement_speed;
-
-                            player_path.clear();
-
-                            let _ = sink.try_seek(std::time::Duration::from_secs(0));
+                            if options[1] {
+                                game_state = GameState::GameOver
+                            } else {
+                                physics = match &replay_playback {
+                                    Some(replay) => replay.start_state(),
+                                    None => PhysicsState::new()
+                                };
+                                previous_physics = physics.clone();
+                                replay_recording.inputs.clear();
+                                in_custom_level = false;
+
+                                player_path.clear();
+
+                                let _ = sink.try_seek(std::time::Duration::from_secs(0));
+                            }
                         }
                     }
+
+                    tick += 1;
                 }
 
                 if rl.is_key_pressed(KeyboardKey::KEY_B) {
@@ -1193,9 +1057,8 @@ async fn main() {
                     }
                 }
 
-                if rl.is_mouse_button_released(MouseButton::MOUSE_BUTTON_LEFT) ||
-                rl.is_key_released(KeyboardKey::KEY_SPACE) {
-                    on_orb = true
+                if rl.is_key_pressed(KeyboardKey::KEY_P) {
+                    replay::save_last_attempt(&last_attempt);
                 }
 
                 reset_menu_music = true;
@@ -1203,6 +1066,10 @@ async fn main() {
             GameState::GameOver => {
                 restart_button.update(&rl, delta_time);
 
+                if rl.is_key_pressed(KeyboardKey::KEY_P) {
+                    replay::save_last_attempt(&last_attempt);
+                }
+
                 if restart_button.is_clicked(&rl) {
                     game_state = GameState::Menu;
                     attempt += 1;
@@ -1215,40 +1082,15 @@ async fn main() {
                 search_button.update(&rl, delta_time);
                 keybinds_button.update(&rl, delta_time);
                 download_level_button.update(&rl, delta_time);
-                clear_level_button.update(&rl, delta_time);
 
                 if menu_button.is_clicked(&rl) {
                     game_state = GameState::Menu;
                 }
 
-                if clear_level_button.is_clicked(&rl) {
-                    level_string = default_level.to_string();
-                }
-
                 if create_button.is_clicked(&rl) {
-                    parts = level_string.split(";;;").collect();
-                    _level_metadata = parts[0];
-                    _object_string = parts[1];
-                    load_level(
-                        &mut _level_metadata.to_string(),
-                        &mut _object_string.to_string(),
-                        &mut object_grid,
-                        &mut bg_red,
-                        &mut bg_green,
-                        &mut bg_blue,
-                        &mut ground_red,
-                        &mut ground_green,
-                        &mut ground_blue,
-                        &mut current_mode,
-                        song_selected,
-                        &mut current_song,
-                        true,
-                        true
-                    );
-
-                    from_editor = true;
-
-                    game_state = GameState::Editor;
+                    library_needs_refresh = true;
+                    library_message.clear();
+                    game_state = GameState::LevelLibrary;
                 }
 
                 if featured_button.is_clicked(&rl) {
@@ -1263,6 +1105,181 @@ async fn main() {
                     game_state = GameState::EditorKeybinds
                 }
             }
+            GameState::LevelLibrary => {
+                menu_button.update(&rl, delta_time);
+                library_new_button.update(&rl, delta_time);
+                library_rename_button.update(&rl, delta_time);
+                library_open_button.update(&rl, delta_time);
+                library_duplicate_button.update(&rl, delta_time);
+                library_delete_button.update(&rl, delta_time);
+
+                if library_needs_refresh {
+                    match library::list_levels(levels_folder) {
+                        Ok(levels) => library_levels = levels,
+                        Err(error) => {
+                            library_levels.clear();
+                            library_message = error;
+                        }
+                    }
+
+                    if !library_levels.iter().any(|level| Some(&level.name) == library_selected.as_ref()) {
+                        library_selected = None;
+                    }
+                    library_scroll = library_scroll.min(library_levels.len().saturating_sub(LIBRARY_ROWS));
+                    library_needs_refresh = false;
+                }
+
+                if menu_button.is_clicked(&rl) {
+                    library_delete_confirm = false;
+                    game_state = GameState::CreatorMenu;
+                }
+
+                // Scrolling through the list
+                if rl.get_mouse_wheel_move() < 0.0 && library_scroll + LIBRARY_ROWS < library_levels.len() {
+                    library_scroll += 1;
+                } else if rl.get_mouse_wheel_move() > 0.0 && library_scroll > 0 {
+                    library_scroll -= 1;
+                }
+
+                // Clicking a level picks it
+                if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+                    for (row, level) in library_levels.iter().skip(library_scroll).take(LIBRARY_ROWS).enumerate() {
+                        if library_row_rect(row).check_collision_point_rec(mouse_pos) {
+                            library_selected = Some(level.name.clone());
+                            library_rename_text = level.name.clone();
+                            library_delete_confirm = false;
+                        }
+                    }
+                }
+
+                if library_rename_textbox.is_clicked(&rl) {
+                    library_rename_textbox.active = true
+                }
+
+                if library_rename_textbox.is_not_clicked(&rl) {
+                    library_rename_textbox.active = false
+                }
+
+                library_rename_textbox.input(&mut library_rename_text, &rl);
+
+                for button in [&mut library_rename_button, &mut library_open_button, &mut library_duplicate_button, &mut library_delete_button] {
+                    button.is_disabled = library_selected.is_none();
+                }
+                library_delete_button.text = if library_delete_confirm { "Are you sure?" } else { "Delete" }.to_string();
+
+                if library_new_button.is_clicked(&rl) {
+                    match library::create_level(levels_folder, default_level) {
+                        Ok(name) => {
+                            library_rename_text = name.clone();
+                            library_selected = Some(name);
+                            library_message.clear();
+                        }
+                        Err(error) => library_message = error
+                    }
+                    library_delete_confirm = false;
+                    library_needs_refresh = true;
+                }
+
+                if let Some(selected) = library_selected.clone() {
+                    if library_rename_button.is_clicked(&rl) {
+                        match library::rename_level(levels_folder, &selected, &library_rename_text) {
+                            Ok(name) => {
+                                library_rename_text = name.clone();
+                                library_selected = Some(name);
+                                library_message.clear();
+                            }
+                            Err(error) => library_message = error
+                        }
+                        library_needs_refresh = true;
+                    }
+
+                    if library_duplicate_button.is_clicked(&rl) {
+                        match library::duplicate_level(levels_folder, &selected) {
+                            Ok(name) => {
+                                library_rename_text = name.clone();
+                                library_selected = Some(name);
+                                library_message.clear();
+                            }
+                            Err(error) => library_message = error
+                        }
+                        library_needs_refresh = true;
+                    }
+
+                    // Deleting needs two clicks since it can't be undone
+                    if library_delete_button.is_clicked(&rl) {
+                        if library_delete_confirm {
+                            match library::delete_level(levels_folder, &selected) {
+                                Ok(()) => {
+                                    library_selected = None;
+                                    library_rename_text.clear();
+                                    library_message.clear();
+                                }
+                                Err(error) => library_message = error
+                            }
+                            library_delete_confirm = false;
+                            library_needs_refresh = true;
+                        } else {
+                            library_delete_confirm = true;
+                        }
+                    }
+
+                    if library_open_button.is_clicked(&rl) {
+                        open_level_path = library::level_path(levels_folder, &selected);
+
+                        match fs::read_to_string(&open_level_path) {
+                            Ok(text) => {
+                                level_string = text;
+                                library_message.clear();
+
+                                level_load_error = load_level(
+                                    &level_string,
+                                    &mut object_grid,
+                                    &mut bg_red,
+                                    &mut bg_green,
+                                    &mut bg_blue,
+                                    &mut ground_red,
+                                    &mut ground_green,
+                                    &mut ground_blue,
+                                    &mut level_color_channels,
+                                    &mut level_info,
+                                    &mut current_mode,
+                                    song_selected,
+                                    &mut current_song,
+                                    true,
+                                    true
+                                ).err();
+
+                                // If the level is broken the editor opens empty and shows what went wrong
+                                if level_load_error.is_some() {
+                                    object_grid.clear();
+                                    level_info = LevelInfo::default();
+                                }
+
+                                // Levels without a name or author get the ones from your levels and your account
+                                if level_info.name.is_empty() {
+                                    level_info.name = selected.clone();
+                                }
+
+                                if let (true, Some(session)) = (level_info.author.is_empty(), &save_data.session) {
+                                    level_info.author = session.user.clone();
+                                }
+
+                                // Undoing shouldn't bring back stuff from the last level that was open
+                                edit_history.clear();
+
+                                from_editor = true;
+                                library_delete_confirm = false;
+
+                                game_state = GameState::Editor;
+                            }
+                            Err(error) => {
+                                library_message = format!("Failed to open {}: {}", selected, error);
+                                library_needs_refresh = true;
+                            }
+                        }
+                    }
+                }
+            }
             GameState::Editor => {
                 if active_popup == ActivePopup::None {
                     build_tab_button.update(&rl, delta_time);
@@ -1279,32 +1296,84 @@ async fn main() {
 
                     // Scales all the object buttons based on if they are being hovered
                     for obj_btn in &mut obj_btns_vec {
-                        obj_btn.btn.update(&rl, delta_time);
+                        if obj_btn.page == object_page {
+                            obj_btn.btn.update(&rl, delta_time);
+                        }
+                    }
+
+                    // The palette has a tab for every category and a search box that looks through all of them
+                    if active_tab == EditorTab::Build {
+                        let last_category = object_category;
+                        let last_search = object_search.clone();
+
+                        for (category, category_button) in &mut category_buttons {
+                            category_button.update(&rl, delta_time);
+
+                            if category_button.is_clicked(&rl) {
+                                object_category = *category;
+                                object_search.clear();
+                            }
+                        }
+
+                        if object_search_textbox.is_clicked(&rl) {
+                            object_search_textbox.active = true
+                        }
+
+                        if object_search_textbox.is_not_clicked(&rl) {
+                            object_search_textbox.active = false
+                        }
+
+                        object_search_textbox.input(&mut object_search, &rl);
+
+                        if object_category != last_category || object_search != last_search {
+                            obj_btns_vec = object_buttons(&object_registry, object_category, &object_search);
+                            object_page_count = obj_btns_vec.last().map(|obj_btn| obj_btn.page + 1).unwrap_or(1);
+                            object_page = 0;
+                        }
+                    } else {
+                        object_search_textbox.active = false
+                    }
+
+                    // Long tabs get split into pages
+                    if object_page_count > 1 && active_tab == EditorTab::Build {
+                        previous_object_page.update(&rl, delta_time);
+                        next_object_page.update(&rl, delta_time);
+
+                        if previous_object_page.is_clicked(&rl) {
+                            object_page = if object_page == 0 { object_page_count - 1 } else { object_page - 1 };
+                        }
+
+                        if next_object_page.is_clicked(&rl) {
+                            object_page = (object_page + 1) % object_page_count;
+                        }
                     }
 
                     if delete_tab_button.is_clicked(&rl) {
                         active_tab = EditorTab::Delete;
                     }
 
-                    if one_pressed
+                    // Typing in the search box shouldn't also switch tabs or move objects
+                    let typing = object_search_textbox.active;
+
+                    if (one_pressed && !typing)
                     || build_tab_button.is_clicked(&rl) {
                         active_tab = EditorTab::Build;
                     }
 
-                    if two_pressed
+                    if (two_pressed && !typing)
                     || edit_tab_button.is_clicked(&rl) {
                         active_tab = EditorTab::Edit;
                     }
 
-                    if three_pressed
+                    if (three_pressed && !typing)
                     || delete_tab_button.is_clicked(&rl) {
                         active_tab = EditorTab::Delete;
                     }
 
                     // This just checks if any of the buttons for selecting an object to place is clicked
                     for obj_btn in &obj_btns_vec {
-                        if obj_btn.btn.is_clicked(&rl) && active_tab == EditorTab::Build {
-                            current_object = obj_btn.obj_id + _advanced_page_number
+                        if obj_btn.page == object_page && obj_btn.btn.is_clicked(&rl) && active_tab == EditorTab::Build {
+                            current_object = obj_btn.obj_id
                         }
                     }
 
@@ -1327,15 +1396,54 @@ async fn main() {
                                 snapped_x,
                                 snapped_y,
                                 current_object,
-                                &mut selected_object,
-                                &mut no_touch_toggle,
-                                &mut hide_toggle,
-                                &mut object_settings,
+                                &object_registry,
+                                &object_columns,
+                                &mut edit_history,
                                 &rl
                             );
                         }
                     }
 
+                    // This handles selecting objects in the edit tab
+                    // Clicking picks one object and dragging picks everything in the box
+                    // Holding shift adds to the selection and holding ctrl takes away from it
+                    let mouse_world = Vector2::new((mouse_x + cam_pos_x * 5) as f32, (mouse_y - cam_pos_y * 5) as f32);
+                    if active_tab == EditorTab::Edit {
+                        let mouse_position = rl.get_mouse_position();
+                        let on_a_button = [
+                            &editor_back,
+                            &level_options_button,
+                            &level_save_button,
+                            &playtest_button,
+                            &level_upload_button,
+                            &no_touch_toggle,
+                            &hide_toggle,
+                            &object_settings
+                        ].iter().any(|button| button.is_hovered(mouse_position));
+
+                        if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT)
+                        && mouse_y < 400
+                        && !on_a_button {
+                            selection_start = Some(mouse_world);
+                        }
+
+                        if rl.is_mouse_button_released(MouseButton::MOUSE_BUTTON_LEFT) {
+                            if let Some(start) = selection_start.take() {
+                                let select_mode = if rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) || rl.is_key_down(KeyboardKey::KEY_RIGHT_SHIFT) {
+                                    SelectMode::Add
+                                } else if rl.is_key_down(KeyboardKey::KEY_LEFT_CONTROL) || rl.is_key_down(KeyboardKey::KEY_RIGHT_CONTROL) {
+                                    SelectMode::Subtract
+                                } else {
+                                    SelectMode::Replace
+                                };
+
+                                editor::select_objects(&mut object_grid, &object_columns, start, mouse_world, select_mode);
+                            }
+                        }
+                    } else {
+                        selection_start = None;
+                    }
+
                     if level_options_button.is_clicked(&rl) {
                         if current_mode == "1" {
                             set_level_type_normal.is_disabled = false;
@@ -1344,6 +1452,9 @@ async fn main() {
                             set_level_type_normal.is_disabled = true;
                             set_level_type_plat.is_disabled = false;
                         }
+
+                        channel_color_text = channel_editor_text(&level_color_channels, channel_editor_channel);
+                        channel_error = None;
                         game_state = GameState::LevelOptions;
                     }
 
@@ -1395,12 +1506,14 @@ async fn main() {
                             }
                         }
 
-                        game_state = GameState::CreatorMenu;
+                        level_load_error = None;
+                        library_needs_refresh = true;
+                        game_state = GameState::LevelLibrary;
                     }
 
                     // This just handles saving the level when the save button is clicked
                     if level_save_button.is_clicked(&rl) {
-                        level_string = get_level_text(
+                        let new_level_string = get_level_text(
                             &current_mode,
                             current_song,
                             bg_red,
@@ -1409,27 +1522,32 @@ async fn main() {
                             ground_red as u8,
                             ground_green as u8,
                             ground_blue as u8,
+                            &level_color_channels,
+                            &level_info,
                             &object_grid
                         );
+                        level_string = stamp_level_text(new_level_string, &level_string, &mut level_info, unix_time());
 
-                        let write_result = fs::write("./save-data/levels/level.txt", &level_string);
+                        let write_result = write_file_atomically(&open_level_path, &level_string);
+                        editor_autosave.saved(rl.get_time());
 
                         println!("{:?}", write_result);
+                        level_load_error = None;
                     }
 
                     // This handles playtesting the level
                     if playtest_button.is_clicked(&rl) {
-                        player.y = 500.0;
-                        world_offset = -(start_pos as f32 - 200.0);
-                        rotation = 0.0;
-                        gravity = default_gravity;
-                        jump_force = default_jump_force;
-                        current_gamemode = GameMode::Cube;
-                        cc_1003 = Color::LIME;
+                        physics = PhysicsState::new();
+                        physics.world_offset = -(start_pos as f32 - 200.0);
+                        previous_physics = physics.clone();
+                        timestep = FixedTimestep::new();
+                        color_transitions.clear();
+                        color_channels = level_color_channels.clone();
+                        object_columns = ObjectColumns::new(&object_grid);
+                        object_columns_revision = None;
+                        replay_recording = Replay::new(&current_mode, &object_grid, physics.world_offset);
+                        replay_playback = None;
                         in_custom_level = true;
-                        velocity_y = 0.0;
-                        player_cam_y = 0;
-                        movement_speed = default_movement_speed;
 
                         let mut obj_index = 0;
                         while obj_index < object_grid.len() {
@@ -1441,8 +1559,7 @@ async fn main() {
                         }
 
                         // This handles stopping the menu music and starting the level music
-                        level_music_file = BufReader::new(File::open(format!("{}", main_levels[current_song as usize].song)).expect("Failed to open MP3 file"));
-                        _level_music = Decoder::new(level_music_file).expect("Failed to decode MP3 file");
+                        _level_music = open_level_music(&level_info, &main_levels[current_song as usize].song);
                         sink.stop();
                         sink.append(_level_music);
                         sink.play();
@@ -1454,173 +1571,117 @@ async fn main() {
                     }
 
                     // This function checks for most of the keybinds in the editor (not all)
-                    editor::keybinds_manager(&mut object_grid, &rl, &mut start_pos);
+                    if !typing {
+                        editor::keybinds_manager(
+                            &mut object_grid,
+                            &mut edit_history,
+                            &mut clipboard,
+                            snapped_x,
+                            snapped_y,
+                            &rl,
+                            &mut start_pos
+                        );
+                    }
+
+                    // Undoing or selecting can change what the selected objects are set to so the buttons get updated every frame
+                    editor::update_selection_buttons(
+                        &object_grid,
+                        &mut no_touch_toggle,
+                        &mut hide_toggle,
+                        &mut object_settings
+                    );
 
                     if level_upload_button.is_clicked(&rl) {
+                        // The upload page starts with what the level options have
+                        level_name = level_info.name.clone();
+                        level_desc = level_info.desc.clone();
+                        online_level_upload_diff = level_info.difficulty.unwrap_or(0);
+
                         game_state = GameState::LevelUpload
                     }
 
                     if no_touch_toggle.is_clicked(&rl) {
-                        let mut obj_index = 0;
-                        while obj_index < object_grid.len() {
-                            if object_grid[obj_index].selected {
-                                if object_grid[obj_index].no_touch == 0 {
-                                    object_grid[obj_index].no_touch = 1;
-                                    no_touch_toggle.is_disabled = false
-                                } else {
-                                    object_grid[obj_index].no_touch = 0;
-                                    no_touch_toggle.is_disabled = true
-                                }
-                                obj_index += 1;
+                        edit_history.change_selected(&mut object_grid, EditKind::Toggle, rl.get_time(), |object| {
+                            if object.no_touch == 0 {
+                                object.no_touch = 1;
+                                no_touch_toggle.is_disabled = false
                             } else {
-                                obj_index += 1;
+                                object.no_touch = 0;
+                                no_touch_toggle.is_disabled = true
                             }
-                        }
+                        });
                     }
 
                     if hide_toggle.is_clicked(&rl) {
-                        let mut obj_index = 0;
-                        while obj_index < object_grid.len() {
-                            if object_grid[obj_index].selected {
-                                if object_grid[obj_index].hide == 0 {
-                                    object_grid[obj_index].hide = 1;
-                                    hide_toggle.is_disabled = false
-                                } else {
-                                    object_grid[obj_index].hide = 0;
-                                    hide_toggle.is_disabled = true
-                                }
-                                obj_index += 1;
+                        edit_history.change_selected(&mut object_grid, EditKind::Toggle, rl.get_time(), |object| {
+                            if object.hide == 0 {
+                                object.hide = 1;
+                                hide_toggle.is_disabled = false
                             } else {
-                                obj_index += 1;
+                                object.hide = 0;
+                                hide_toggle.is_disabled = true
                             }
-                        }
+                        });
                     }
                 } else {
-                    // This is all the code for the edit object popup
-                    set_color_red.update(&rl, delta_time);
-                    set_color_green.update(&rl, delta_time);
-                    set_color_blue.update(&rl, delta_time);
-                    set_color_type_bg.update(&rl, delta_time);
-                    set_color_type_grnd.update(&rl, delta_time);
-
-                    if color_red_textbox.is_clicked(&rl) {
-                        color_red_textbox.active = true
-                    }
-
-                    if color_green_textbox.is_clicked(&rl) {
-                        color_green_textbox.active = true
-                    }
-
-                    if color_blue_textbox.is_clicked(&rl) {
-                        color_blue_textbox.active = true
-                    }
-
-                    if color_red_textbox.is_not_clicked(&rl) {
-                        color_red_textbox.active = false
-                    }
+                    // This is all the code for the object settings popup
+                    // Numbers get set when you press enter or click off the textbox and everything else gets set when it's clicked
+                    let mut changed_field: Option<(usize, String)> = None;
+
+                    for (index, row) in inspector.iter_mut().enumerate() {
+                        if row.field.is_typed() {
+                            if row.textbox.is_clicked(&rl) {
+                                row.textbox.active = true
+                            }
 
-                    if color_green_textbox.is_not_clicked(&rl) {
-                        color_green_textbox.active = false
-                    }
+                            let finished = row.textbox.active
+                                && (row.textbox.is_not_clicked(&rl) || rl.is_key_pressed(KeyboardKey::KEY_ENTER));
 
-                    if color_blue_textbox.is_not_clicked(&rl) {
-                        color_blue_textbox.active = false
-                    }
+                            row.textbox.input(&mut row.text, &rl);
 
-                    color_red_textbox.input(&mut color_red_text, &rl);
-                    color_green_textbox.input(&mut color_green_text, &rl);
-                    color_blue_textbox.input(&mut color_blue_text, &rl);
+                            if finished {
+                                row.textbox.active = false;
 
-                    // These all handle setting the properties of a color trigger
-                    if set_color_red.is_clicked(&rl) {
-                        let mut obj_index = 0;
-                        while obj_index < object_grid.len() {
-                            if object_grid[obj_index].selected
-                            && object_grid[obj_index].id == 23
-                            && color_red_text.len() > 0
-                            && color_red_text.parse::<u16>().unwrap() <= 255
-                            && color_red_text.parse::<i16>().unwrap() >= 0 {
-                                if let Some(properties) = object_grid[obj_index].properties.as_mut() {
-                                    properties[0] = color_red_text.clone()
-                                }
-                                obj_index += 1;
-                            } else {
-                                obj_index += 1;
-                            }
-                        }
-                    }
-
-                    if set_color_green.is_clicked(&rl) {
-                        let mut obj_index = 0;
-                        while obj_index < object_grid.len() {
-                            if object_grid[obj_index].selected
-                            && object_grid[obj_index].id == 23
-                            && color_green_text.len() > 0
-                            && color_green_text.parse::<u16>().unwrap() <= 255
-                            && color_green_text.parse::<i16>().unwrap() >= 0 {
-                                if let Some(properties) = object_grid[obj_index].properties.as_mut() {
-                                    properties[1] = color_green_text.clone()
+                                if !row.text.is_empty() && Some(&row.text) != row.field.value.as_ref() {
+                                    changed_field = Some((index, row.text.clone()));
+                                } else {
+                                    row.text = row.field.value.clone().unwrap_or_default();
                                 }
-                                obj_index += 1;
-                            } else {
-                                obj_index += 1;
                             }
-                        }
-                    }
+                        } else {
+                            row.button.update(&rl, delta_time);
 
-                    if set_color_blue.is_clicked(&rl) {
-                        let mut obj_index = 0;
-                        while obj_index < object_grid.len() {
-                            if object_grid[obj_index].selected
-                            && object_grid[obj_index].id == 23
-                            && color_blue_text.len() > 0
-                            && color_blue_text.parse::<u16>().unwrap() <= 255
-                            && color_blue_text.parse::<i16>().unwrap() >= 0 {
-                                if let Some(properties) = object_grid[obj_index].properties.as_mut() {
-                                    properties[2] = color_blue_text.clone()
-                                }
-                                obj_index += 1;
-                            } else {
-                                obj_index += 1;
+                            if row.button.is_clicked(&rl) {
+                                changed_field = Some((index, row.field.next_value()));
                             }
                         }
                     }
 
-                    if set_color_type_bg.is_clicked(&rl) {
-                        let mut obj_index = 0;
-                        while obj_index < object_grid.len() {
-                            if object_grid[obj_index].selected {
-                                if let Some(properties) = object_grid[obj_index].properties.as_mut() {
-                                    properties[3] = "1".to_string();
-                                    set_color_type_bg.is_disabled = false;
-                                    set_color_type_grnd.is_disabled = true
-                                }
-                                obj_index += 1;
-                            } else {
-                                obj_index += 1;
+                    if let Some((index, text)) = changed_field {
+                        let field = inspector[index].field.clone();
+
+                        match field.parse(&text) {
+                            Ok(value) => {
+                                edit_history.change_where(
+                                    &mut object_grid,
+                                    EditKind::Property,
+                                    rl.get_time(),
+                                    |object| field.applies_to(object),
+                                    |object| field.apply(object, &value, &object_registry)
+                                );
+                                inspector_error = None;
                             }
+                            Err(error) => inspector_error = Some(error)
                         }
-                    }
 
-                    if set_color_type_grnd.is_clicked(&rl) {
-                        let mut obj_index = 0;
-                        while obj_index < object_grid.len() {
-                            if object_grid[obj_index].selected {
-                                if let Some(properties) = object_grid[obj_index].properties.as_mut() {
-                                    properties[3] = "2".to_string();
-                                    set_color_type_bg.is_disabled = true;
-                                    set_color_type_grnd.is_disabled = false
-                                }
-                                obj_index += 1;
-                            } else {
-                                obj_index += 1;
-                            }
-                        }
+                        refresh_inspector_rows(&mut inspector, inspect(&object_grid, &object_registry));
                     }
                 }
 
                 if object_settings.is_clicked(&rl)
-                && selected_object == 23 {
+                && !object_settings.is_disabled {
+                    inspector = inspector_rows(inspect(&object_grid, &object_registry));
+                    inspector_error = None;
                     active_popup = ActivePopup::ObjectSettings
                 }
 
@@ -1635,56 +1696,146 @@ async fn main() {
             }
             GameState::LevelOptions => {
                 level_options_back.update(&rl, delta_time);
+                level_info_button.update(&rl, delta_time);
+                difficulty_prev_button.update(&rl, delta_time);
+                difficulty_next_button.update(&rl, delta_time);
                 set_level_type_normal.update(&rl, delta_time);
                 set_level_type_plat.update(&rl, delta_time);
 
+                channel_prev_button.update(&rl, delta_time);
+                channel_next_button.update(&rl, delta_time);
+                channel_set_button.update(&rl, delta_time);
+                channel_remove_button.update(&rl, delta_time);
+
                 if level_options_back.is_clicked(&rl) {
                     game_state = GameState::Editor;
-                }
 
-                // These handle the sliders for setting the colors of your level
-                if red_bg_slider.is_clicked(&rl) {
-                    red_bg_slider_pos = mouse_y as u8 - 25;
-                    bg_red = red_bg_slider_pos - 75;
+                    // The level options aren't in the undo history so they get saved once you leave
+                    editor_autosave.changed(rl.get_time());
                 }
 
-                if green_bg_slider.is_clicked(&rl) {
-                    green_bg_slider_pos = mouse_y as u8 - 25;
-                    bg_green = green_bg_slider_pos - 75;
+                if level_info_button.is_clicked(&rl) {
+                    level_options_info = !level_options_info;
+                    level_info_button.text = if level_options_info { "Colors" } else { "Level Info" }.to_string();
                 }
 
-                if blue_bg_slider.is_clicked(&rl) {
-                    blue_bg_slider_pos = mouse_y as u8 - 25;
-                    bg_blue = blue_bg_slider_pos - 75;
-                }
+                if level_options_info {
+                    // These are the same order as the level_info_textboxes
+                    let info_text = [&mut level_info.name, &mut level_info.author, &mut level_info.desc, &mut level_info.song_file];
+                    for (textbox, text) in level_info_textboxes.iter_mut().zip(info_text) {
+                        if textbox.is_clicked(&rl) {
+                            textbox.active = true;
+                        }
 
+                        if textbox.is_not_clicked(&rl) {
+                            textbox.active = false;
+                        }
 
-                if red_ground_slider.is_clicked(&rl) {
-                    red_ground_slider_pos = mouse_y - 25;
-                    ground_red = red_ground_slider_pos - 355;
-                }
+                        textbox.input(text, &rl);
+                    }
 
-                if green_ground_slider.is_clicked(&rl) {
-                    green_ground_slider_pos = mouse_y - 25;
-                    ground_green = green_ground_slider_pos - 355;
-                }
+                    // The difficulty goes back to not being picked if you go below the first one
+                    if difficulty_prev_button.is_clicked(&rl) {
+                        level_info.difficulty = match level_info.difficulty {
+                            Some(0) | Some(1) | None => None,
+                            Some(difficulty) => Some(difficulty - 1)
+                        };
+                    }
 
-                if blue_ground_slider.is_clicked(&rl) {
-                    blue_ground_slider_pos = mouse_y - 25;
-                    ground_blue = blue_ground_slider_pos - 355;
-                }
+                    if difficulty_next_button.is_clicked(&rl) {
+                        level_info.difficulty = Some(level_info.difficulty.map_or(1, |difficulty| (difficulty + 1).min(MAX_DIFFICULTY)));
+                    }
+                } else {
+                    // This handles picking and setting the level's color channels
+                    // Holding shift goes through them 10 at a time
+                    let channel_step = if rl.is_key_down(KeyboardKey::KEY_LEFT_SHIFT) { 10 } else { 1 };
+                    let previous_channel = channel_editor_channel;
 
-                // These handle setting the level to normal and platformer
-                if set_level_type_normal.is_clicked(&rl) {
-                    set_level_type_normal.is_disabled = false;
-                    set_level_type_plat.is_disabled = true;
-                    current_mode = "1".to_string();
-                }
+                    if channel_prev_button.is_clicked(&rl) {
+                        channel_editor_channel = channel_editor_channel.saturating_sub(channel_step).max(1);
+                    }
+
+                    if channel_next_button.is_clicked(&rl) {
+                        channel_editor_channel = (channel_editor_channel + channel_step).min(colors::MAX_COLOR_CHANNEL);
+                    }
+
+                    if channel_editor_channel != previous_channel {
+                        channel_color_text = channel_editor_text(&level_color_channels, channel_editor_channel);
+                        channel_error = None;
+                    }
+
+                    for (textbox, text) in channel_color_textboxes.iter_mut().zip(channel_color_text.iter_mut()) {
+                        if textbox.is_clicked(&rl) {
+                            textbox.active = true;
+                        }
 
-                if set_level_type_plat.is_clicked(&rl) {
-                    set_level_type_normal.is_disabled = true;
-                    set_level_type_plat.is_disabled = false;
-                    current_mode = "2".to_string();
+                        if textbox.is_not_clicked(&rl) {
+                            textbox.active = false;
+                        }
+
+                        textbox.input(text, &rl);
+                    }
+
+                    if channel_set_button.is_clicked(&rl) {
+                        match channel_color_text.iter().map(|text| text.parse::<u8>()).collect::<Result<Vec<u8>, _>>() {
+                            Ok(color) => {
+                                level_color_channels.insert(channel_editor_channel, (color[0], color[1], color[2]));
+                                channel_error = None;
+                            }
+                            Err(_) => channel_error = Some("Colors have to be from 0 to 255".to_string())
+                        }
+                    }
+
+                    if channel_remove_button.is_clicked(&rl) {
+                        level_color_channels.remove(&channel_editor_channel);
+                        channel_color_text = channel_editor_text(&level_color_channels, channel_editor_channel);
+                        channel_error = None;
+                    }
+
+                    // These handle the sliders for setting the colors of your level
+                    if red_bg_slider.is_clicked(&rl) {
+                        red_bg_slider_pos = mouse_y as u8 - 25;
+                        bg_red = red_bg_slider_pos - 75;
+                    }
+
+                    if green_bg_slider.is_clicked(&rl) {
+                        green_bg_slider_pos = mouse_y as u8 - 25;
+                        bg_green = green_bg_slider_pos - 75;
+                    }
+
+                    if blue_bg_slider.is_clicked(&rl) {
+                        blue_bg_slider_pos = mouse_y as u8 - 25;
+                        bg_blue = blue_bg_slider_pos - 75;
+                    }
+
+
+                    if red_ground_slider.is_clicked(&rl) {
+                        red_ground_slider_pos = mouse_y - 25;
+                        ground_red = red_ground_slider_pos - 355;
+                    }
+
+                    if green_ground_slider.is_clicked(&rl) {
+                        green_ground_slider_pos = mouse_y - 25;
+                        ground_green = green_ground_slider_pos - 355;
+                    }
+
+                    if blue_ground_slider.is_clicked(&rl) {
+                        blue_ground_slider_pos = mouse_y - 25;
+                        ground_blue = blue_ground_slider_pos - 355;
+                    }
+
+                    // These handle setting the level to normal and platformer
+                    if set_level_type_normal.is_clicked(&rl) {
+                        set_level_type_normal.is_disabled = false;
+                        set_level_type_plat.is_disabled = true;
+                        current_mode = "1".to_string();
+                    }
+
+                    if set_level_type_plat.is_clicked(&rl) {
+                        set_level_type_normal.is_disabled = true;
+                        set_level_type_plat.is_disabled = false;
+                        current_mode = "2".to_string();
+                    }
                 }
             }
             GameState::LevelSelect => {
@@ -1701,13 +1852,11 @@ async fn main() {
                 }
 
                 // This handles entering a level when enter is pressed
-                if rl.is_key_pressed(KeyboardKey::KEY_ENTER) {
-                    parts = main_levels[current_level].data.split(";;;").collect();
-                    _level_metadata = parts[0];
-                    _object_string = parts[1];
-                    load_level(
-                        &mut _level_metadata.to_string(),
-                        &mut _object_string.to_string(),
+                // Pressing R watches the replay saved from the last time you beat it
+                let watch_replay = rl.is_key_pressed(KeyboardKey::KEY_R);
+                if rl.is_key_pressed(KeyboardKey::KEY_ENTER) || watch_replay {
+                    let level_loaded = load_level(
+                        &main_levels[current_level].data,
                         &mut object_grid,
                         &mut bg_red,
                         &mut bg_green,
@@ -1715,6 +1864,8 @@ async fn main() {
                         &mut ground_red,
                         &mut ground_green,
                         &mut ground_blue,
+                        &mut level_color_channels,
+                        &mut level_info,
                         &mut current_mode,
                         song_selected,
                         &mut current_song,
@@ -1722,29 +1873,58 @@ async fn main() {
                         false
                     );
 
-                    level_music_file = BufReader::new(File::open(format!("{}", main_levels[current_level].song)).expect("Failed to open MP3 file"));
-                    _level_music = Decoder::new(level_music_file).expect("Failed to decode MP3 file");
-                    sink.stop();
-                    sink.append(_level_music);
-                    sink.play();
+                    if level_loaded.is_ok() {
+                        level_music_file = BufReader::new(File::open(format!("{}", main_levels[current_level].song)).expect("Failed to open MP3 file"));
+                        _level_music = Decoder::new(level_music_file).expect("Failed to decode MP3 file");
+                        sink.stop();
+                        sink.append(_level_music);
+                        sink.play();
 
-                    player.y = 500.0;
-                    world_offset = 0.0;
-                    rotation = 0.0;
-                    gravity = default_gravity;
-                    jump_force = default_jump_force;
-                    current_gamemode = GameMode::Cube;
-                    cc_1003 = Color::LIME;
-                    in_custom_level = false;
-                    velocity_y = 0.0;
-                    player_cam_y = 0;
-                    movement_speed = default_movement_speed;
+                        physics = PhysicsState::new();
+                        previous_physics = physics.clone();
+                        timestep = FixedTimestep::new();
+                        color_transitions.clear();
+                        color_channels = level_color_channels.clone();
+                        object_columns = ObjectColumns::new(&object_grid);
+                        object_columns_revision = None;
+                        replay_recording = Replay::new(&current_mode, &object_grid, physics.world_offset);
+                        replay_playback = None;
+                        in_custom_level = false;
 
-                    from_editor = false;
-                    player_path.clear();
+                        from_editor = false;
+                        player_path.clear();
+
+                        attempt = 1;
+                        game_state = GameState::Playing;
+                        replay_error = None;
+
+                        if watch_replay {
+                            let replay_file = replay::replay_path(replay_recording.level_hash);
+                            let replay_loaded = match fs::read_to_string(&replay_file) {
+                                Ok(replay_data) => Replay::parse(&replay_data).map_err(|error| error.to_string()),
+                                Err(_) => Err("You haven't beaten this level yet so there's no replay".to_string())
+                            };
+
+                            match replay_loaded {
+                                Ok(replay) if replay.level_hash == replay_recording.level_hash => {
+                                    physics = replay.start_state();
+                                    previous_physics = physics.clone();
+                                    replay_playback = Some(replay);
+                                }
+                                Ok(_) => replay_error = Some("The replay is for a different version of this level".to_string()),
+                                Err(error) => replay_error = Some(error)
+                            }
+
+                            if replay_error.is_some() {
+                                sink.stop();
+                                sink.append(menu_loop.clone());
+                                sink.play();
+                                game_state = GameState::LevelSelect;
+                            }
+                        }
+                    }
 
-                    attempt = 1;
-                    game_state = GameState::Playing;
+                    level_load_error = level_loaded.err();
                 }
 
                 if reset_menu_music {
@@ -1755,6 +1935,8 @@ async fn main() {
                 }
 
                 if rl.is_key_pressed(KeyboardKey::KEY_B) {
+                    level_load_error = None;
+                    replay_error = None;
                     game_state = GameState::Menu;
                 }
 
@@ -1802,21 +1984,29 @@ async fn main() {
                 if login_button.is_clicked(&rl) {
                     let login_url = login_url.to_owned();
 
-                    let login_result_string = post_request(
+                    let response = post_request(
                         login_url,
                         Some(hashmap! {
                             "user".to_string() => username.clone(),
                             "pass".to_string() => password.clone()
                         })
                     ).await;
-                    login_result = login_result_string;
 
-                    if login_result == "Logged in!" {
-                        logged_in = true;
-                        user = username.clone();
-                        pass = password.clone();
-                    } else if login_result.contains("error code: 1033") {
-                        show_server_down = true
+                    // Only the token you get back is saved and never your password
+                    match parse_login_response(&username, &response, None) {
+                        LoginResponse::LoggedIn(session) => {
+                            logged_in = true;
+                            is_mod = session.is_mod();
+                            login_result = LOGGED_IN_RESPONSE.to_string();
+                            save_data.session = Some(session);
+                        }
+                        LoginResponse::TokenRejected => login_result = response,
+                        LoginResponse::Failed(problem) => {
+                            if is_server_down(&response) {
+                                show_server_down = true
+                            }
+                            login_result = problem;
+                        }
                     }
 
                     register_result = "".to_string();
@@ -1836,7 +2026,7 @@ async fn main() {
                     register_result = register_result_string;
                     login_result = "".to_string();
 
-                    if register_result.contains("error code: 1033") {
+                    if is_server_down(&register_result) {
                         show_server_down = true
                     }
                 };
@@ -1866,13 +2056,24 @@ async fn main() {
 
                 if menu_button.is_clicked(&rl) {
                     show_server_down = false;
+                    level_verify_result = "".to_string();
                     game_state = GameState::CreatorMenu
                 }
 
                 // This handles uploading a level
                 if upload_button.is_clicked(&rl) {
+                    level_verify_result = match verify::verify_saved_replay(&current_mode, &object_grid, &object_registry) {
+                        Ok(_) => "".to_string(),
+                        Err(problem) => problem
+                    };
+
                     // You can only upload a level if your logged into an account
-                    if logged_in {
+                    // It also has to be beaten from the start first so impossible levels can't get uploaded
+                    if !logged_in || save_data.session.is_none() {
+                        level_upload_result = "Not logged in!".to_string();
+                    } else if !level_verify_result.is_empty() {
+                        level_upload_result = "Not verified!".to_string();
+                    } else if let Some(session) = save_data.session.clone() {
                         let level_data = get_level_text(
                             current_mode.as_str(),
                             current_song,
@@ -1882,6 +2083,8 @@ async fn main() {
                             ground_red as u8,
                             ground_green as u8,
                             ground_blue as u8,
+                            &level_color_channels,
+                            &level_info,
                             &object_grid
                         );
 
@@ -1891,19 +2094,25 @@ async fn main() {
                                 "name".to_string() => level_name.clone(),
                                 "desc".to_string() => level_desc.clone(),
                                 "data".to_string() => level_data,
-                                "creator".to_string() => user.clone(),
-                                "pass".to_string() => pass.clone(),
+                                "creator".to_string() => session.user,
+                                "token".to_string() => session.token.unwrap_or_default(),
                                 "diff".to_string() => online_level_upload_diff.to_string()
                             })
                         ).await;
 
                         println!("{}", level_upload_result);
 
-                        if level_upload_result.contains("error code: 1033") {
+                        if is_server_down(&level_upload_result) {
                             show_server_down = true
                         }
-                    } else {
-                        level_upload_result = "Not logged in!".to_string();
+
+                        if is_token_rejected(&level_upload_result) {
+                            save_data.session = None;
+                            logged_in = false;
+                            is_mod = false;
+                            login_result = "Please log in again!".to_string();
+                            game_state = GameState::AccountPage;
+                        }
                     }
                 }
 
@@ -1941,10 +2150,8 @@ async fn main() {
 
                 // This handles entering the level if the play button is clicked
                 if level_play_button.is_clicked(&rl) {
-                    let parts: Vec<&str> = online_level_data.split(";;;").collect();
                     let level_loaded = load_level(
-                        &mut parts[0].to_string(),
-                        &mut parts[1].to_string(),
+                        &online_level_data,
                         &mut object_grid,
                         &mut bg_red,
                         &mut bg_green,
@@ -1952,6 +2159,8 @@ async fn main() {
                         &mut ground_red,
                         &mut ground_green,
                         &mut ground_blue,
+                        &mut level_color_channels,
+                        &mut level_info,
                         &mut current_mode,
                         song_selected,
                         &mut current_song,
@@ -1959,37 +2168,38 @@ async fn main() {
                         false
                     );
 
-                    if level_loaded == "ok" {
-                        level_music_file = BufReader::new(File::open(format!("{}", main_levels[current_song as usize].song)).expect("Failed to open MP3 file"));
-                        _level_music = Decoder::new(level_music_file).expect("Failed to decode MP3 file");
+                    if level_loaded.is_ok() {
+                        _level_music = open_level_music(&level_info, &main_levels[current_song as usize].song);
                         sink.stop();
                         sink.append(_level_music);
                         sink.play();
                     }
 
-                    player.y = 500.0;
-                    world_offset = 0.0;
-                    rotation = 0.0;
-                    gravity = default_gravity;
-                    jump_force = default_jump_force;
-                    current_gamemode = GameMode::Cube;
-                    cc_1003 = Color::LIME;
+                    physics = PhysicsState::new();
+                    previous_physics = physics.clone();
+                    timestep = FixedTimestep::new();
+                    color_transitions.clear();
+                    color_channels = level_color_channels.clone();
+                    object_columns = ObjectColumns::new(&object_grid);
+                    object_columns_revision = None;
+                    replay_recording = Replay::new(&current_mode, &object_grid, physics.world_offset);
+                    replay_playback = None;
                     in_custom_level = true;
-                    velocity_y = 0.0;
-                    player_cam_y = 0;
-                    movement_speed = default_movement_speed;
 
                     // This makes sure that it only enters the level if the level works with the version of the client
-                    if level_loaded == "ok" {
+                    if level_loaded.is_ok() {
                         from_editor = false;
                         player_path.clear();
 
                         attempt = 1;
                         game_state = GameState::Playing;
                     }
+
+                    level_load_error = level_loaded.err();
                 }
 
                 if menu_button.is_clicked(&rl) {
+                    level_load_error = None;
                     game_state = GameState::SearchPage
                 }
 
@@ -2004,6 +2214,7 @@ async fn main() {
                 if menu_button.is_clicked(&rl) {
                     show_level_not_found = false;
                     show_server_down = false;
+                    level_load_error = None;
                     game_state = GameState::CreatorMenu
                 }
 
@@ -2025,7 +2236,7 @@ async fn main() {
                     if cached_levels.contains_key(&level_id) {
                         level_download_result = cached_levels.get(&level_id).unwrap().to_string();
 
-                        parse_level_download_response(
+                        level_load_error = parse_level_download_response(
                             level_download_result.clone(),
                             &mut online_level_name,
                             &mut online_level_desc,
@@ -2033,10 +2244,12 @@ async fn main() {
                             &mut online_level_rated,
                             &mut online_level_creator,
                             &mut online_level_data
-                        );
+                        ).err();
 
                         show_level_not_found = false;
-                        game_state = GameState::LevelPage
+                        if level_load_error.is_none() {
+                            game_state = GameState::LevelPage
+                        }
                     } else {
                         level_download_result = get_request(
                             download_url.clone(),
@@ -2046,7 +2259,7 @@ async fn main() {
                         ).await;
 
                         if level_download_result.contains(";;;;;") {
-                            parse_level_download_response(
+                            level_load_error = parse_level_download_response(
                                 level_download_result.clone(),
                                 &mut online_level_name,
                                 &mut online_level_desc,
@@ -2054,12 +2267,14 @@ async fn main() {
                                 &mut online_level_rated,
                                 &mut online_level_creator,
                                 &mut online_level_data
-                            );
+                            ).err();
 
                             show_level_not_found = false;
-                            cached_levels.insert(level_id.clone(), level_download_result.clone());
-                            game_state = GameState::LevelPage
-                        } else if level_download_result.contains("error code: 1033") {
+                            if level_load_error.is_none() {
+                                cached_levels.insert(level_id.clone(), level_download_result.clone());
+                                game_state = GameState::LevelPage
+                            }
+                        } else if is_server_down(&level_download_result) {
                             show_server_down = true
                         } else {
                             show_level_not_found = true
@@ -2087,18 +2302,30 @@ async fn main() {
                 // Only I (Puppet) can rate levels at the moment
                 // It's enforced on the server too dw :3
                 if submit_rating_button.is_clicked(&rl) {
-                    level_rate_result = post_request(
-                        rate_url.clone(),
-                        Some(hashmap! {
-                            "user".to_string() => user.clone(),
-                            "pass".to_string() => pass.clone(),
-                            "diff".to_string() => format!("{}", online_level_rate_diff),
-                            "id".to_string() => level_id.clone()
-                        })
-                    ).await;
+                    if let Some(session) = save_data.session.clone() {
+                        level_rate_result = post_request(
+                            rate_url.clone(),
+                            Some(hashmap! {
+                                "user".to_string() => session.user,
+                                "token".to_string() => session.token.unwrap_or_default(),
+                                "diff".to_string() => format!("{}", online_level_rate_diff),
+                                "id".to_string() => level_id.clone()
+                            })
+                        ).await;
+
+                        if level_rate_result == "Rating applied!" {
+                            online_level_diff = online_level_rate_diff
+                        }
 
-                    if level_rate_result == "Rating applied!" {
-                        online_level_diff = online_level_rate_diff
+                        if is_token_rejected(&level_rate_result) {
+                            save_data.session = None;
+                            logged_in = false;
+                            is_mod = false;
+                            login_result = "Please log in again!".to_string();
+                            game_state = GameState::AccountPage;
+                        }
+                    } else {
+                        level_rate_result = "Not logged in!".to_string();
                     }
                 }
             }
@@ -2107,6 +2334,7 @@ async fn main() {
 
                 legacy_grnd_bg_toggle.update(&rl, delta_time);
                 game_over_screen_toggle.update(&rl, delta_time);
+                smooth_movement_toggle.update(&rl, delta_time);
 
                 if menu_button.is_clicked(&rl) {
                     game_state = GameState::Menu
@@ -2131,7 +2359,109 @@ async fn main() {
                         game_over_screen_toggle.is_disabled = false
                     }
                 }
+
+                if smooth_movement_toggle.is_clicked(&rl) {
+                    if options[2] {
+                        options[2] = false;
+                        smooth_movement_toggle.is_disabled = true
+                    } else {
+                        options[2] = true;
+                        smooth_movement_toggle.is_disabled = false
+                    }
+                }
+            }
+            GameState::PlayerCustomization => {
+                menu_button.update(&rl, delta_time);
+
+                if menu_button.is_clicked(&rl) {
+                    game_state = GameState::Menu
+                }
+
+                for (gamemode, button) in GAMEMODES.iter().zip(customize_gamemode_buttons.iter_mut()) {
+                    button.update(&rl, delta_time);
+
+                    if button.is_clicked(&rl) {
+                        customize_gamemode = *gamemode;
+                    }
+                }
+
+                if rl.is_mouse_button_pressed(MouseButton::MOUSE_BUTTON_LEFT) {
+                    let icons = &player_icons[player::gamemode_index(customize_gamemode)];
+                    for (index, icon) in icons.iter().enumerate() {
+                        if icon_choice_rect(index).check_collision_point_rec(mouse_pos) {
+                            save_data.player.set_icon(customize_gamemode, icon.icon);
+                        }
+                    }
+
+                    for (index, color) in PLAYER_COLORS.iter().enumerate() {
+                        if color_choice_rect(index, 400.0).check_collision_point_rec(mouse_pos) {
+                            save_data.player.primary = *color;
+                            cc_1003 = rgb_color(save_data.player.primary);
+                        }
+
+                        if color_choice_rect(index, 480.0).check_collision_point_rec(mouse_pos) {
+                            save_data.player.secondary = *color;
+                        }
+                    }
+                }
+            }
+        }
+
+        // Stars and beating levels get saved right away so they can't get lost
+        if saved_save_data.as_ref() != Some(&save_data) {
+            match write_save(std::path::Path::new(SAVE_PATH), &save_data) {
+                Ok(()) => saved_save_data = Some(save_data.clone()),
+                Err(error) => println!("Failed to save: {}", error)
+            }
+        }
+
+        // The level gets saved once you stop editing for a bit or leave the editor
+        if was_editing {
+            let time = rl.get_time();
+            let still_editing = game_state == GameState::Editor || game_state == GameState::LevelOptions;
+
+            if editor_autosave_revision != Some(edit_history.revision()) {
+                if editor_autosave_revision.is_some() {
+                    editor_autosave.changed(time);
+                }
+                editor_autosave_revision = Some(edit_history.revision());
+            }
+
+            if editor_autosave.should_save(time) || (!still_editing && editor_autosave.is_pending()) {
+                let new_level_string = get_level_text(
+                    &current_mode,
+                    current_song,
+                    bg_red,
+                    bg_green,
+                    bg_blue,
+                    ground_red as u8,
+                    ground_green as u8,
+                    ground_blue as u8,
+                    &level_color_channels,
+                    &level_info,
+                    &object_grid
+                );
+                let new_level_string = stamp_level_text(new_level_string, &level_string, &mut level_info, unix_time());
+
+                if new_level_string != level_string {
+                    level_string = new_level_string;
+
+                    if let Err(error) = write_file_atomically(&open_level_path, &level_string) {
+                        println!("Failed to autosave the level: {}", error);
+                    }
+                }
+
+                editor_autosave.saved(time);
             }
+        } else {
+            editor_autosave_revision = None;
+        }
+
+        // The editor changes the level a lot so the object columns get rebuilt whenever it does
+        if game_state == GameState::Editor
+        && object_columns_revision != Some(edit_history.revision()) {
+            object_columns = ObjectColumns::new(&object_grid);
+            object_columns_revision = Some(edit_history.revision());
         }
 
         // Rendering
@@ -2148,7 +2478,7 @@ async fn main() {
 
                 d.draw_text(&format!("Version: {}", version), 10, 10, 15, Color::WHITE);
                 d.draw_text(&format!("Latest Version: {}", *latest_version.lock().unwrap()), 10, 30, 15, Color::WHITE);
-                d.draw_text(&format!("Stars: {}", stars), 10, 50, 15, Color::WHITE);
+                d.draw_text(&format!("Stars: {}", save_data.stars), 10, 50, 15, Color::WHITE);
 
                 d.draw_text(&format!("Made by Thepuppet57"), 10, d.get_screen_height() - 30, 25, Color::WHITE);
 
@@ -2198,8 +2528,19 @@ async fn main() {
 
                 account_page_button.draw(false, None, 1.0, false, &mut d);
                 settings_button.draw(false, None, 1.0, false, &mut d);
+                customize_button.draw(false, None, 1.0, false, &mut d);
             }
             GameState::Playing => {
+                // With smooth movement on the player gets drawn between the last two physics ticks
+                let view = if options[2] {
+                    physics::interpolate(&previous_physics, &physics, timestep.alpha())
+                } else {
+                    physics.clone()
+                };
+
+                // The ground and background scroll by time instead of by frame
+                let frame_scale = delta_time * physics::TICKS_PER_SECOND;
+
                 d.clear_background(Color::WHITE);
                 d.draw_texture_ex(&game_bg, Vector2::new(bg_offset, -150.0), 0.0, 0.7, cc_1001);
                 d.draw_texture_ex(&game_bg, Vector2::new(bg_offset + 1344.0, -150.0), 0.0, 0.7, cc_1001);
@@ -2208,27 +2549,30 @@ async fn main() {
                 && bg_offset < 1344.0
                 && options[0] {
                     if current_mode == "1"
-                    || moving_direction == 1 {
-                        bg_offset -= movement_speed / 7.0;
-                    } else if moving_direction == 2 {
-                        bg_offset += movement_speed / 7.0;
+                    || view.moving_direction == 1 {
+                        bg_offset -= view.movement_speed / 7.0 * frame_scale;
+                    } else if view.moving_direction == 2 {
+                        bg_offset += view.movement_speed / 7.0 * frame_scale;
                     }
                 } else {
                     bg_offset = 0.0;
                 }
 
-                d.draw_rectangle_pro(
-                    player,
-                    Vector2::new(player.width / 2.0, player.height / 2.0),
-                    rotation,
-                    cc_1003,
+                draw_player(
+                    &mut d,
+                    &player_icons,
+                    &save_data.player,
+                    view.gamemode,
+                    Rectangle::from(view.player),
+                    view.rotation,
+                    cc_1003
                 );
 
                 // Draw ground
                 for i in -1..7 {
                     d.draw_texture_ex(
                         &ground_texture,
-                        Vector2::new(i as f32 * 150.0 + grnd_offset, 520.0 - player_cam_y as f32),
+                        Vector2::new(i as f32 * 150.0 + grnd_offset, 520.0 - view.player_cam_y as f32),
                         0.0,
                         0.2,
                         cc_1002,
@@ -2239,254 +2583,91 @@ async fn main() {
                 && grnd_offset < 140.0
                 && options[0] {
                     if current_mode == "1"
-                    || moving_direction == 1 {
-                        grnd_offset -= movement_speed
-                    } else if moving_direction == 2 {
-                        grnd_offset += movement_speed
+                    || view.moving_direction == 1 {
+                        grnd_offset -= view.movement_speed * frame_scale
+                    } else if view.moving_direction == 2 {
+                        grnd_offset += view.movement_speed * frame_scale
                     }
                 } else {
                     grnd_offset = 0.0
                 }
 
                 // This handles rendering all the objects
-                for i in &object_grid {
-                    let object_x = i.x as f32 + world_offset as f32 + 20.0;
-                    let object_y = i.y as f32 - player_cam_y as f32 + 20.0;
-                    if i.x as f32 + world_offset < d.get_screen_width() as f32 &&
-                    i.x as f32 + world_offset > -40.0 && i.hide == 0 {
-                        if from_editor || (i.id != 15 && i.id != 23) {
-                            if i.id != 17 && i.id != 18 && i.id != 19 && i.id != 20 {
-                                d.draw_texture_pro(
-                                    &texture_ids.get(i.id as usize).unwrap(),
-                                    Rectangle::new(
-                                        0.0,
-                                        0.0,
-                                        texture_ids.get(i.id as usize).unwrap().width as f32,
-                                        texture_ids.get(i.id as usize).unwrap().height as f32
-                                    ),
-                                    Rectangle::new(
-                                        object_x,
-                                        object_y,
-                                        texture_ids.get(i.id as usize).unwrap().width as f32 * 0.05,
-                                        texture_ids.get(i.id as usize).unwrap().height as f32 * 0.05
-                                    ),
-                                    Vector2::new(
-                                        texture_ids.get(i.id as usize).unwrap().width as f32 / 2.0 * 0.05,
-                                        texture_ids.get(i.id as usize).unwrap().height as f32 / 2.0 * 0.05
-                                    ),
-                                    i.rotation as f32,
-                                    cc_1004
-                                );
-                            } else {
-                                d.draw_texture_pro(
-                                    &texture_ids.get(i.id as usize).unwrap(),
-                                    Rectangle::new(
-                                        0.0,
-                                        0.0,
-                                        texture_ids.get(i.id as usize).unwrap().width as f32,
-                                        texture_ids.get(i.id as usize).unwrap().height as f32
-                                    ),
-                                    Rectangle::new(
-                                        object_x + 10.0,
-                                        object_y,
-                                        texture_ids.get(i.id as usize).unwrap().width as f32 * 0.1,
-                                        texture_ids.get(i.id as usize).unwrap().height as f32 * 0.1
-                                    ),
-                                    Vector2::new(
-                                        texture_ids.get(i.id as usize).unwrap().width as f32 / 2.0 * 0.1,
-                                        texture_ids.get(i.id as usize).unwrap().height as f32 / 2.0 * 0.1
-                                    ),
-                                    i.rotation as f32,
-                                    cc_1004
-                                );
-                            }
+                let on_screen_objects = object_columns.in_range(
+                    (-40.0 - view.world_offset).floor() as i32,
+                    (d.get_screen_width() as f32 - view.world_offset).ceil() as i32
+                );
+                for index in &on_screen_objects {
+                    let i = &object_grid[*index];
+                    let object_x = i.x as f32 + view.world_offset as f32 + 20.0;
+                    let object_y = i.y as f32 - view.player_cam_y as f32 + 20.0;
+                    if i.x as f32 + view.world_offset < d.get_screen_width() as f32 &&
+                    i.x as f32 + view.world_offset > -40.0 && i.hide == 0 {
+                        let editor_only = object_registry.get(i.id).is_some_and(|object_type| object_type.editor_only);
+                        if from_editor || !editor_only {
+                            draw_object(&mut d, &object_registry, &object_textures, i, object_x, object_y, object_tint(&color_channels, i, cc_1004));
                         }
                     }
                 }
 
                 // Draw obstacles (old)
                 // for obstacle in &obstacles {
-                //     let actual_x = obstacle.x + world_offset;
+                //     let actual_x = obstacle.x + view.world_offset;
                 //     d.draw_texture_ex(&texture_ids.get(&1).unwrap(), Vector2::new(actual_x, 480.0), 0.0, 0.05, cc_1004);
                 // }
 
                 // This handles rendering all the hitboxes if debug mode is on
                 if show_debug_text {
-                    for object in &object_grid {
-                        if object.x as f32 + world_offset < d.get_screen_width() as f32 &&
-                        object.x as f32 + world_offset > -40.0 &&
+                    for object in on_screen_objects.iter().map(|index| &object_grid[*index]) {
+                        if object.x as f32 + view.world_offset < d.get_screen_width() as f32 &&
+                        object.x as f32 + view.world_offset > -40.0 &&
                         object.no_touch == 0 {
-                            if object.id == 1 {
-                                d.draw_rectangle_lines(
-                                    object.x + world_offset as i32 + 15,
-                                    object.y + 10 - player_cam_y,
-                                    10,
-                                    20,
-                                    Color::RED
-                                );
-                            }
-
-                            if object.id == 2 ||
-                            object.id == 10 ||
-                            object.id == 11 ||
-                            object.id == 12 ||
-                            object.id == 13 ||
-                            object.id == 14 {
-                                if current_mode == "1" {
-                                    d.draw_rectangle_lines(
-                                        object.x + world_offset as i32,
-                                        object.y + 10 - player_cam_y,
-                                        3,
-                                        20,
-                                        Color::RED
-                                    );
-                                } else {
-                                    d.draw_rectangle_lines(
-                                        object.x + world_offset as i32,
-                                        object.y + 20 - player_cam_y,
-                                        3,
-                                        3,
-                                        Color::BLUEVIOLET
-                                    );
-
+                            if let Some(object_type) = object_registry.get(object.id) {
+                                let color = match object_type.behavior {
+                                    Behavior::Hazard | Behavior::DeathOrb => Color::RED,
+                                    Behavior::Solid => Color::BLUEVIOLET,
+                                    _ => Color::TEAL
+                                };
+
+                                for hitbox in object_type.hitboxes(object.rotation, &current_mode) {
                                     d.draw_rectangle_lines(
-                                        object.x + 40 + world_offset as i32,
-                                        object.y + 20 - player_cam_y,
-                                        3,
-                                        3,
-                                        Color::BLUEVIOLET
+                                        object.x + view.world_offset as i32 + hitbox.x as i32,
+                                        object.y + hitbox.y as i32 - view.player_cam_y,
+                                        hitbox.width as i32,
+                                        hitbox.height as i32,
+                                        color
                                     );
                                 }
-    
-                                d.draw_rectangle_lines(
-                                    object.x + world_offset as i32 + 3,
-                                    object.y - player_cam_y,
-                                    37,
-                                    3,
-                                    Color::BLUEVIOLET
-                                );
-    
-                                d.draw_rectangle_lines(
-                                    object.x + world_offset as i32 + 3,
-                                    object.y + 38 - player_cam_y,
-                                    37,
-                                    3,
-                                    Color::BLUEVIOLET
-                                );
-    
-                                d.draw_rectangle_lines(
-                                    object.x + world_offset as i32 + 80,
-                                    object.y - player_cam_y + 10,
-                                    3,
-                                    20,
-                                    Color::GREEN
-                                );
-                            }
-    
-                            if object.id == 3 {
-                                d.draw_rectangle_lines(
-                                    object.x + world_offset as i32,
-                                    object.y + 35 - player_cam_y,
-                                    40,
-                                    5,
-                                    Color::TEAL
-                                );
-                            }
-    
-                            if object.id == 4
-                            || object.id == 22 {
-                                d.draw_rectangle_lines(
-                                    object.x - 10 + world_offset as i32,
-                                    object.y - 10 - player_cam_y,
-                                    60,
-                                    60,
-                                    Color::TEAL
-                                );
-                            }
-    
-                            if object.id == 5 || object.id == 6 {
-                                d.draw_rectangle_lines(
-                                    object.x + world_offset as i32 + if object.rotation == 0 || object.rotation == 180 || object.rotation == -180 { 10 } else { -20 },
-                                    object.y - if object.rotation == 0 || object.rotation == 180 || object.rotation == -180 { 11 } else { -11 } - player_cam_y,
-                                    if object.rotation == 0 || object.rotation == 180 || object.rotation == -180 { 20 } else { 80 },
-                                    if object.rotation == 0 || object.rotation == 180 || object.rotation == -180 { 80 } else { 20 },
-                                    Color::TEAL
-                                );
-                            }
-    
-                            if object.id == 7 {
-                                d.draw_rectangle_lines(
-                                    object.x + world_offset as i32 + 15,
-                                    object.y + if object.rotation > 145 || object.rotation < -145 { 5 } else { 25 } - player_cam_y,
-                                    10,
-                                    10,
-                                    Color::RED
-                                );
-                            }
-    
-                            if object.id == 8
-                            || object.id == 9
-                            || object.id == 24 {
-                                d.draw_rectangle_lines(
-                                    object.x + world_offset as i32 + if object.rotation == 0 || object.rotation == 180 || object.rotation == -180 { 10 } else { -20 },
-                                    object.y - if object.rotation == 0 || object.rotation == 180 || object.rotation == -180 { 11 } else { -11 } - player_cam_y,
-                                    if object.rotation == 0 || object.rotation == 180 || object.rotation == -180 { 20 } else { 80 },
-                                    if object.rotation == 0 || object.rotation == 180 || object.rotation == -180 { 80 } else { 20 },
-                                    Color::TEAL
-                                );
-                            }
-    
-                            if object.id == 15 {
-                                d.draw_rectangle_lines(
-                                    object.x + world_offset as i32,
-                                    object.y - player_cam_y,
-                                    40,
-                                    40,
-                                    Color::TEAL
-                                );
-                            }
-
-                            if object.id == 17 ||
-                            object.id == 18 ||
-                            object.id == 19 ||
-                            object.id == 20 {
-                                d.draw_rectangle_lines(
-                                    object.x + world_offset as i32 + if object.rotation == 0 || object.rotation == 180 || object.rotation == -180 { 10 } else { -20 },
-                                    object.y - if object.rotation == 0 || object.rotation == 180 || object.rotation == -180 { 11 } else { -11 } - player_cam_y,
-                                    if object.rotation == 0 || object.rotation == 180 || object.rotation == -180 { 20 } else { 80 },
-                                    if object.rotation == 0 || object.rotation == 180 || object.rotation == -180 { 80 } else { 20 },
-                                    Color::TEAL
-                                );
                             }
                         }
                     }
 
                     d.draw_rectangle_lines(
-                        small_player.x as i32,
-                        small_player.y as i32,
-                        small_player.width as i32,
-                        small_player.height as i32,
+                        view.small_player.x as i32,
+                        view.small_player.y as i32,
+                        view.small_player.width as i32,
+                        view.small_player.height as i32,
                         Color::BLACK
                     );
 
                     d.draw_rectangle_lines(
-                        centered_player.x as i32,
-                        centered_player.y as i32,
-                        centered_player.width as i32,
-                        centered_player.height as i32,
+                        view.centered_player.x as i32,
+                        view.centered_player.y as i32,
+                        view.centered_player.width as i32,
+                        view.centered_player.height as i32,
                         Color::WHITE
                     );
                 }
 
-                if from_editor || current_gamemode == GameMode::Wave {
+                if from_editor || view.gamemode == GameMode::Wave {
                     for point in &player_path {
-                        if point.x as f32 + world_offset < d.get_screen_width() as f32 &&
-                        point.x as f32 + world_offset > 60.0 {
+                        if point.x as f32 + view.world_offset < d.get_screen_width() as f32 &&
+                        point.x as f32 + view.world_offset > 60.0 {
                             d.draw_circle(
-                                point.x as i32 + world_offset as i32,
-                                point.y as i32 - player_cam_y,
+                                point.x as i32 + view.world_offset as i32,
+                                point.y as i32 - view.player_cam_y,
                                 5.0,
-                                if current_gamemode == GameMode::Wave && !from_editor {
+                                if view.gamemode == GameMode::Wave && !from_editor {
                                     Color::BLUE
                                 } else {
                                     Color::GREEN
@@ -2498,10 +2679,20 @@ async fn main() {
 
                 d.draw_text(&format!("Attempt: {}", attempt), 10, 10, 20, Color::WHITE);
 
+                if replay_playback.is_some() {
+                    d.draw_text(
+                        "Watching replay",
+                        d.get_screen_width() - d.measure_text("Watching replay", 20) - 10,
+                        10,
+                        20,
+                        Color::WHITE
+                    );
+                }
+
                 if show_debug_text {
-                    d.draw_text(&format!("Velocity Y: {}", velocity_y), 10, 40, 20, Color::LIME);
-                    d.draw_text(&format!("On Ground: {}", is_on_ground), 10, 70, 20, Color::LIME);
-                    d.draw_text(&format!("Touching block ceiling: {}", touching_block_ceiling), 10, 100, 20, Color::LIME);
+                    d.draw_text(&format!("Velocity Y: {}", view.velocity_y), 10, 40, 20, Color::LIME);
+                    d.draw_text(&format!("On Ground: {}", view.is_on_ground), 10, 70, 20, Color::LIME);
+                    d.draw_text(&format!("Touching block ceiling: {}", view.touching_block_ceiling), 10, 100, 20, Color::LIME);
                     d.draw_text(&format!("Points in path: {}", player_path.len()), 10, 130, 20, Color::LIME);
                 }
             }
@@ -2524,65 +2715,61 @@ async fn main() {
                 featured_button.draw(false, None, 1.0, false, &mut d);
                 search_button.draw(false, None, 1.0, false, &mut d);
                 keybinds_button.draw(false, None, 1.0, false, &mut d);
-                clear_level_button.draw(false, None, 1.0, false, &mut d);
 
                 if not_done_yet_text {
                     d.draw_text("This will be added eventually!", 250, 30, 30, Color::WHITE);
                 }
             }
+            GameState::LevelLibrary => {
+                d.clear_background(Color::WHITE);
+                d.draw_texture_ex(&menu_bg, Vector2::new(-150.0, -90.0), 0.0, 0.8, Color { r:50, g:50, b:50, a:255 });
+
+                d.draw_text("Your Levels", 250, 30, 40, Color::WHITE);
+
+                let now = std::time::SystemTime::now();
+                for (row, level) in library_levels.iter().skip(library_scroll).take(LIBRARY_ROWS).enumerate() {
+                    let rect = library_row_rect(row);
+                    let selected = library_selected.as_ref() == Some(&level.name);
+
+                    d.draw_rectangle_rec(rect, if selected { Color { r:0, g:121, b:241, a:200 } } else { Color { r:0, g:0, b:0, a:150 } });
+                    d.draw_text(&level.name, rect.x as i32 + 10, rect.y as i32 + 12, 25, Color::WHITE);
+                    d.draw_text(&format!("{} objects", level.object_count), rect.x as i32 + 370, rect.y as i32 + 15, 20, Color::WHITE);
+                    d.draw_text(if level.platformer { "Platformer" } else { "Normal" }, rect.x as i32 + 500, rect.y as i32 + 15, 20, Color::WHITE);
+                    d.draw_text(&library::time_ago(level.modified, now), rect.x as i32 + 620, rect.y as i32 + 15, 20, Color::LIGHTGRAY);
+                }
+
+                if library_levels.is_empty() {
+                    d.draw_text("You don't have any levels yet!", 220, 250, 30, Color::WHITE);
+                }
+
+                menu_button.draw(false, None, 1.0, false, &mut d);
+                library_new_button.draw(false, None, 1.0, false, &mut d);
+                library_rename_textbox.draw(library_rename_text.clone(), &mut d);
+                library_rename_button.draw(false, None, 1.0, false, &mut d);
+                library_open_button.draw(false, None, 1.0, false, &mut d);
+                library_duplicate_button.draw(false, None, 1.0, false, &mut d);
+                library_delete_button.draw(false, None, 1.0, false, &mut d);
+
+                d.draw_text(&library_message, 20, 540, 20, Color::RED);
+            }
             GameState::Editor => {
                 d.clear_background(Color::WHITE);
                 d.draw_texture_ex(&game_bg, Vector2::new(0.0, -150.0), 0.0, 0.7, cc_1001);
 
-                // This handles rendering all the objects
-                for i in &object_grid {
+                // This handles rendering all the objects that are on screen
+                for index in object_columns.in_range(cam_pos_x * 5 - 120, cam_pos_x * 5 + d.get_screen_width() + 40) {
+                    let i = &object_grid[index];
                     let object_x = i.x as f32 - cam_pos_x as f32 * 5.0 + 20.0;
                     let object_y = i.y as f32 + cam_pos_y as f32 * 5.0 + 20.0;
-                    if i.id != 17 && i.id != 18 && i.id != 19 && i.id != 20 {
-                        d.draw_texture_pro(
-                            &texture_ids.get(i.id as usize).unwrap(),
-                            Rectangle::new(
-                                0.0,
-                                0.0,
-                                texture_ids.get(i.id as usize).unwrap().width as f32,
-                                texture_ids.get(i.id as usize).unwrap().height as f32
-                            ),
-                            Rectangle::new(
-                                object_x,
-                                object_y,
-                                texture_ids.get(i.id as usize).unwrap().width as f32 * 0.05,
-                                texture_ids.get(i.id as usize).unwrap().height as f32 * 0.05
-                            ),
-                            Vector2::new(
-                                texture_ids.get(i.id as usize).unwrap().width as f32 / 2.0 * 0.05,
-                                texture_ids.get(i.id as usize).unwrap().height as f32 / 2.0 * 0.05
-                            ),
-                            i.rotation as f32,
-                            if i.selected { Color::LIME } else if i.hide == 1 { Color { r:0, g:0, b:0, a:0 } } else { cc_1004 }
-                        );
-                    } else {
-                        d.draw_texture_pro(
-                            &texture_ids.get(i.id as usize).unwrap(),
-                            Rectangle::new(
-                                0.0,
-                                0.0,
-                                texture_ids.get(i.id as usize).unwrap().width as f32,
-                                texture_ids.get(i.id as usize).unwrap().height as f32
-                            ),
-                            Rectangle::new(
-                                object_x + 10.0,
-                                object_y,
-                                texture_ids.get(i.id as usize).unwrap().width as f32 * 0.1,
-                                texture_ids.get(i.id as usize).unwrap().height as f32 * 0.1
-                            ),
-                            Vector2::new(
-                                texture_ids.get(i.id as usize).unwrap().width as f32 / 2.0 * 0.1,
-                                texture_ids.get(i.id as usize).unwrap().height as f32 / 2.0 * 0.1
-                            ),
-                            i.rotation as f32,
-                            if i.selected { Color::LIME } else if i.hide == 1 { Color { r:0, g:0, b:0, a:0 } } else { cc_1004 }
-                        );
-                    }
+                    draw_object(
+                        &mut d,
+                        &object_registry,
+                        &object_textures,
+                        i,
+                        object_x,
+                        object_y,
+                        if i.selected { Color::LIME } else if i.hide == 1 { Color { r:0, g:0, b:0, a:0 } } else { object_tint(&level_color_channels, i, cc_1004) }
+                    );
                 }
 
                 // This handles rendering the line that shows where the level starts
@@ -2638,24 +2825,82 @@ async fn main() {
                 object_settings.draw(false, None, 1.0, false, &mut d);
 
                 if edit_not_done_yet {
-                    d.draw_text("Click to select!", 270, 490, 40, Color::WHITE);
+                    d.draw_text("Click or drag to select!", 200, 490, 40, Color::WHITE);
+                }
+
+                // This draws the box you drag out when selecting
+                if let Some(start) = selection_start {
+                    let start_x = start.x as i32 - cam_pos_x * 5;
+                    let start_y = start.y as i32 + cam_pos_y * 5;
+
+                    d.draw_rectangle(
+                        start_x.min(mouse_x),
+                        start_y.min(mouse_y),
+                        (start_x - mouse_x).abs(),
+                        (start_y - mouse_y).abs(),
+                        Color { r:0, g:228, b:48, a:50 }
+                    );
+                    d.draw_rectangle_lines(
+                        start_x.min(mouse_x),
+                        start_y.min(mouse_y),
+                        (start_x - mouse_x).abs(),
+                        (start_y - mouse_y).abs(),
+                        Color::LIME
+                    );
                 }
 
                 // Draw all the object buttons
                 if active_tab == EditorTab::Build {
-                    let object_button_texture_scale: f32 = 0.04;
+                    // The tab you are on is white and the rest are gray
+                    // While searching none of them are white because the search looks through all of them
+                    for (category, category_button) in &category_buttons {
+                        category_button.draw(false, None, 1.0, *category != object_category || !object_search.is_empty(), &mut d);
+                    }
+
+                    object_search_textbox.draw(object_search.clone(), &mut d);
 
                     // This handles drawing the buttons used for selecting an object to place
-                    for obj_btn in &obj_btns_vec {
-                        obj_btn.btn.draw(true, Some(texture_ids.get(obj_btn.obj_id as usize).unwrap()), object_button_texture_scale, true, &mut d);
+                    // Every texture is shrunk to fit in its button no matter how big it is
+                    for obj_btn in obj_btns_vec.iter().filter(|obj_btn| obj_btn.page == object_page) {
+                        let texture = object_texture(&object_registry, &object_textures, obj_btn.obj_id);
+                        let thumbnail_scale = (obj_btn.btn.rect.width - 10.0) / texture.width.max(texture.height).max(1) as f32;
+
+                        obj_btn.btn.draw(true, Some(&texture), thumbnail_scale, true, &mut d);
+
+                        if obj_btn.obj_id == current_object {
+                            d.draw_rectangle_lines_ex(obj_btn.btn.rect, 3.0, Color::LIME);
+                        }
+                    }
+
+                    if obj_btns_vec.is_empty() {
+                        d.draw_text("No objects found", 187, 490, 30, Color::GRAY);
+                    }
+
+                    if object_page_count > 1 {
+                        previous_object_page.draw(false, None, 1.0, false, &mut d);
+                        next_object_page.draw(false, None, 1.0, false, &mut d);
+
+                        let page_text = format!("{}/{}", object_page + 1, object_page_count);
+                        d.draw_text(&page_text, 780 - d.measure_text(&page_text, 15) / 2, 504, 15, Color::WHITE);
+                    }
+
+                    // The buttons only have pictures so the name shows up when you hover over one
+                    if let Some(obj_btn) = obj_btns_vec.iter()
+                        .find(|obj_btn| obj_btn.page == object_page && obj_btn.btn.is_hovered(Vector2::new(mouse_x as f32, mouse_y as f32))) {
+                        let name = object_registry.name(obj_btn.obj_id);
+                        let name_width = d.measure_text(name, 15);
+                        let name_x = (mouse_x + 10).min(d.get_screen_width() - name_width - 10);
+
+                        d.draw_rectangle(name_x - 5, mouse_y - 25, name_width + 10, 20, Color { r:0, g:0, b:0, a:200 });
+                        d.draw_text(name, name_x, mouse_y - 22, 15, Color::WHITE);
                     }
                 }
 
-                d.draw_text(&format!("Selected Object: {}", objects.get(current_object as usize).unwrap()), 10, 10, 20, Color::WHITE);
+                d.draw_text(&format!("Selected Object: {}", object_registry.name(current_object)), 10, 10, 20, Color::WHITE);
                 if show_debug_text {
                     d.draw_text(&format!("Camera pos X: {}", cam_pos_x), 10, 40, 20, Color::LIME);
                     d.draw_text(&format!("Camera pos Y: {}", cam_pos_y), 10, 70, 20, Color::LIME);
-                    d.draw_text(&format!("Advanced Page Number: {}", _advanced_page_number), 10, 100, 20, Color::LIME);
+                    d.draw_text(&format!("Object Page: {}", object_page), 10, 100, 20, Color::LIME);
                     d.draw_text(&format!("Mouse X On Grid: {}", snapped_x), 10, 130, 20, Color::LIME);
                     d.draw_text(&format!("Mouse Y On Grid: {}", snapped_y), 10, 160, 20, Color::LIME);
                     d.draw_text(&format!("Mouse X: {}", mouse_x), 10, 190, 20, Color::LIME);
@@ -2664,6 +2909,10 @@ async fn main() {
                     d.draw_text(&format!("Object Grid: {:?}", object_grid), 10, 250, 20, Color::LIME);
                 }
 
+                if let Some(error) = &level_load_error {
+                    draw_level_error(&mut d, error, Some(370));
+                }
+
                 // This handles rendering the stuff in the object settings popup
                 if active_popup == ActivePopup::ObjectSettings {
                     d.draw_rectangle(
@@ -2696,17 +2945,33 @@ async fn main() {
 
                     menu_button.draw(false, None, 1.0, false, &mut d);
 
-                    if selected_object == 23 {
-                        set_color_red.draw(false, None, 1.0, false, &mut d);
-                        set_color_green.draw(false, None, 1.0, false, &mut d);
-                        set_color_blue.draw(false, None, 1.0, false, &mut d);
+                    let selected_count = object_grid.iter().filter(|object| object.selected).count();
+                    d.draw_text(
+                        &format!("Editing {} object{}", selected_count, if selected_count == 1 { "" } else { "s" }),
+                        120,
+                        115,
+                        25,
+                        Color::WHITE
+                    );
+
+                    for row in &inspector {
+                        d.draw_text(
+                            &row.field.name,
+                            row.textbox.rect.x as i32 - 120,
+                            row.textbox.rect.y as i32 + 8,
+                            18,
+                            Color::WHITE
+                        );
 
-                        color_red_textbox.draw(color_red_text.clone(), &mut d);
-                        color_green_textbox.draw(color_green_text.clone(), &mut d);
-                        color_blue_textbox.draw(color_blue_text.clone(), &mut d);
+                        if row.field.is_typed() {
+                            row.textbox.draw(row.text.clone(), &mut d);
+                        } else {
+                            row.button.draw(false, None, 1.0, row.field.value.is_none(), &mut d);
+                        }
+                    }
 
-                        set_color_type_bg.draw(false, None, 1.0, false, &mut d);
-                        set_color_type_grnd.draw(false, None, 1.0, false, &mut d);
+                    if let Some(error) = &inspector_error {
+                        d.draw_text(error, 120, 470, 18, Color::RED);
                     }
                 }
             }
@@ -2714,68 +2979,148 @@ async fn main() {
                 d.clear_background(Color {r:0, g:0, b:75, a:255});
 
                 level_options_back.draw(false, None, 1.0, false, &mut d);
+                level_info_button.draw(false, None, 1.0, false, &mut d);
+
+                if level_options_info {
+                    d.draw_text("Level Name", 20, 95, 20, Color::WHITE);
+                    d.draw_text("Author", 20, 180, 20, Color::WHITE);
+                    d.draw_text("Description", 20, 290, 20, Color::WHITE);
+                    d.draw_text("Custom Song (a file in the Music folder)", 20, 450, 20, Color::WHITE);
+
+                    let info_text = [&level_info.name, &level_info.author, &level_info.desc, &level_info.song_file];
+                    for (textbox, text) in level_info_textboxes.iter().zip(info_text) {
+                        textbox.draw(text.clone(), &mut d);
+                    }
+
+                    d.draw_text("Target Difficulty", 460, 95, 20, Color::WHITE);
+                    difficulty_prev_button.draw(false, None, 1.0, false, &mut d);
+                    difficulty_next_button.draw(false, None, 1.0, false, &mut d);
+
+                    match level_info.difficulty {
+                        Some(difficulty) => {
+                            let face = &difficulties[difficulty as usize];
+                            d.draw_texture_pro(
+                                face,
+                                Rectangle::new(0.0, 0.0, face.width as f32, face.height as f32),
+                                Rectangle::new(520.0, 120.0, 100.0, 100.0),
+                                Vector2::new(0.0, 0.0),
+                                0.0,
+                                Color::WHITE
+                            );
+                        }
+                        None => d.draw_text("Not picked", 515, 160, 20, Color::GRAY)
+                    }
+
+                    let now = std::time::SystemTime::now();
+                    let time_text = |time: Option<u64>| match time {
+                        Some(time) => library::time_ago(Some(from_unix_time(time)), now),
+                        None => "Not saved yet".to_string()
+                    };
+                    d.draw_text(&format!("Created: {}", time_text(level_info.created)), 20, 390, 20, Color::WHITE);
+                    d.draw_text(&format!("Last changed: {}", time_text(level_info.modified)), 20, 420, 20, Color::WHITE);
+                } else {
+
+                    d.draw_rectangle(425, 20, 100, 50, Color {r:255, g:0, b:0, a:255});
+                    d.draw_rectangle(550, 20, 100, 50, Color {r:0, g:255, b:0, a:255});
+                    d.draw_rectangle(675, 20, 100, 50, Color {r:0, g:0, b:255, a:255});
+
+                    d.draw_rectangle_rounded_lines_ex(Rectangle { x:425.0, y:20.0, width:100.0, height:50.0 }, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_rectangle_rounded_lines_ex(Rectangle { x:550.0, y:20.0, width:100.0, height:50.0 }, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_rectangle_rounded_lines_ex(Rectangle { x:675.0, y:20.0, width:100.0, height:50.0 }, 0.0, 4, 5.0, Color::BLACK);
+
+                    d.draw_rectangle(470, 100, 10, 150, Color {r:255, g:0, b:0, a:255});
+                    d.draw_rectangle(595, 100, 10, 150, Color {r:0, g:255, b:0, a:255});
+                    d.draw_rectangle(720, 100, 10, 150, Color {r:0, g:0, b:255, a:255});
 
-                d.draw_rectangle(425, 20, 100, 50, Color {r:255, g:0, b:0, a:255});
-                d.draw_rectangle(550, 20, 100, 50, Color {r:0, g:255, b:0, a:255});
-                d.draw_rectangle(675, 20, 100, 50, Color {r:0, g:0, b:255, a:255});
+                    d.draw_rectangle_rounded_lines_ex(Rectangle {x: 470.0, y: 100.0, width:10.0, height:150.0}, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_rectangle_rounded_lines_ex(Rectangle {x: 595.0, y: 100.0, width:10.0, height:150.0}, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_rectangle_rounded_lines_ex(Rectangle {x: 720.0, y: 100.0, width:10.0, height:150.0}, 0.0, 4, 5.0, Color::BLACK);
 
-                d.draw_rectangle_rounded_lines_ex(Rectangle { x:425.0, y:20.0, width:100.0, height:50.0 }, 0.0, 4, 5.0, Color::BLACK);
-                d.draw_rectangle_rounded_lines_ex(Rectangle { x:550.0, y:20.0, width:100.0, height:50.0 }, 0.0, 4, 5.0, Color::BLACK);
-                d.draw_rectangle_rounded_lines_ex(Rectangle { x:675.0, y:20.0, width:100.0, height:50.0 }, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_rectangle(450, red_bg_slider_pos as i32, 50, 50, Color::WHITE);
+                    d.draw_rectangle(575, green_bg_slider_pos as i32, 50, 50, Color::WHITE);
+                    d.draw_rectangle(700, blue_bg_slider_pos as i32, 50, 50, Color::WHITE);
 
-                d.draw_rectangle(470, 100, 10, 150, Color {r:255, g:0, b:0, a:255});
-                d.draw_rectangle(595, 100, 10, 150, Color {r:0, g:255, b:0, a:255});
-                d.draw_rectangle(720, 100, 10, 150, Color {r:0, g:0, b:255, a:255});
+                    d.draw_rectangle_rounded_lines_ex(Rectangle {x: 450.0, y: red_bg_slider_pos as f32, width:50.0, height:50.0}, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_rectangle_rounded_lines_ex(Rectangle {x: 575.0, y: green_bg_slider_pos as f32, width:50.0, height:50.0}, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_rectangle_rounded_lines_ex(Rectangle {x: 700.0, y: blue_bg_slider_pos as f32, width:50.0, height:50.0}, 0.0, 4, 5.0, Color::BLACK);
 
-                d.draw_rectangle_rounded_lines_ex(Rectangle {x: 470.0, y: 100.0, width:10.0, height:150.0}, 0.0, 4, 5.0, Color::BLACK);
-                d.draw_rectangle_rounded_lines_ex(Rectangle {x: 595.0, y: 100.0, width:10.0, height:150.0}, 0.0, 4, 5.0, Color::BLACK);
-                d.draw_rectangle_rounded_lines_ex(Rectangle {x: 720.0, y: 100.0, width:10.0, height:150.0}, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_text(&format!("{}", bg_red), 435, 25, 50, Color::BLACK);
+                    d.draw_text(&format!("{}", bg_green), 560, 25, 50, Color::BLACK);
+                    d.draw_text(&format!("{}", bg_blue), 685, 25, 50, Color::BLACK);
 
-                d.draw_rectangle(450, red_bg_slider_pos as i32, 50, 50, Color::WHITE);
-                d.draw_rectangle(575, green_bg_slider_pos as i32, 50, 50, Color::WHITE);
-                d.draw_rectangle(700, blue_bg_slider_pos as i32, 50, 50, Color::WHITE);
+                    d.draw_rectangle(425, 300, 100, 50, Color {r:255, g:0, b:0, a:255});
+                    d.draw_rectangle(550, 300, 100, 50, Color {r:0, g:255, b:0, a:255});
+                    d.draw_rectangle(675, 300, 100, 50, Color {r:0, g:0, b:255, a:255});
 
-                d.draw_rectangle_rounded_lines_ex(Rectangle {x: 450.0, y: red_bg_slider_pos as f32, width:50.0, height:50.0}, 0.0, 4, 5.0, Color::BLACK);
-                d.draw_rectangle_rounded_lines_ex(Rectangle {x: 575.0, y: green_bg_slider_pos as f32, width:50.0, height:50.0}, 0.0, 4, 5.0, Color::BLACK);
-                d.draw_rectangle_rounded_lines_ex(Rectangle {x: 700.0, y: blue_bg_slider_pos as f32, width:50.0, height:50.0}, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_rectangle_rounded_lines_ex(Rectangle { x:425.0, y:300.0, width:100.0, height:50.0 }, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_rectangle_rounded_lines_ex(Rectangle { x:550.0, y:300.0, width:100.0, height:50.0 }, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_rectangle_rounded_lines_ex(Rectangle { x:675.0, y:300.0, width:100.0, height:50.0 }, 0.0, 4, 5.0, Color::BLACK);
 
-                d.draw_text(&format!("{}", bg_red), 435, 25, 50, Color::BLACK);
-                d.draw_text(&format!("{}", bg_green), 560, 25, 50, Color::BLACK);
-                d.draw_text(&format!("{}", bg_blue), 685, 25, 50, Color::BLACK);
+                    d.draw_rectangle(470, 380, 10, 150, Color {r:255, g:0, b:0, a:255});
+                    d.draw_rectangle(595, 380, 10, 150, Color {r:0, g:255, b:0, a:255});
+                    d.draw_rectangle(720, 380, 10, 150, Color {r:0, g:0, b:255, a:255});
 
-                d.draw_rectangle(425, 300, 100, 50, Color {r:255, g:0, b:0, a:255});
-                d.draw_rectangle(550, 300, 100, 50, Color {r:0, g:255, b:0, a:255});
-                d.draw_rectangle(675, 300, 100, 50, Color {r:0, g:0, b:255, a:255});
+                    d.draw_rectangle_rounded_lines_ex(Rectangle {x: 470.0, y: 380.0, width:10.0, height:150.0}, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_rectangle_rounded_lines_ex(Rectangle {x: 595.0, y: 380.0, width:10.0, height:150.0}, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_rectangle_rounded_lines_ex(Rectangle {x: 720.0, y: 380.0, width:10.0, height:150.0}, 0.0, 4, 5.0, Color::BLACK);
 
-                d.draw_rectangle_rounded_lines_ex(Rectangle { x:425.0, y:300.0, width:100.0, height:50.0 }, 0.0, 4, 5.0, Color::BLACK);
-                d.draw_rectangle_rounded_lines_ex(Rectangle { x:550.0, y:300.0, width:100.0, height:50.0 }, 0.0, 4, 5.0, Color::BLACK);
-                d.draw_rectangle_rounded_lines_ex(Rectangle { x:675.0, y:300.0, width:100.0, height:50.0 }, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_rectangle(450, red_ground_slider_pos as i32, 50, 50, Color::WHITE);
+                    d.draw_rectangle(575, green_ground_slider_pos as i32, 50, 50, Color::WHITE);
+                    d.draw_rectangle(700, blue_ground_slider_pos as i32, 50, 50, Color::WHITE);
 
-                d.draw_rectangle(470, 380, 10, 150, Color {r:255, g:0, b:0, a:255});
-                d.draw_rectangle(595, 380, 10, 150, Color {r:0, g:255, b:0, a:255});
-                d.draw_rectangle(720, 380, 10, 150, Color {r:0, g:0, b:255, a:255});
+                    d.draw_rectangle_rounded_lines_ex(Rectangle {x: 450.0, y: red_ground_slider_pos as f32, width:50.0, height:50.0}, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_rectangle_rounded_lines_ex(Rectangle {x: 575.0, y: green_ground_slider_pos as f32, width:50.0, height:50.0}, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_rectangle_rounded_lines_ex(Rectangle {x: 700.0, y: blue_ground_slider_pos as f32, width:50.0, height:50.0}, 0.0, 4, 5.0, Color::BLACK);
 
-                d.draw_rectangle_rounded_lines_ex(Rectangle {x: 470.0, y: 380.0, width:10.0, height:150.0}, 0.0, 4, 5.0, Color::BLACK);
-                d.draw_rectangle_rounded_lines_ex(Rectangle {x: 595.0, y: 380.0, width:10.0, height:150.0}, 0.0, 4, 5.0, Color::BLACK);
-                d.draw_rectangle_rounded_lines_ex(Rectangle {x: 720.0, y: 380.0, width:10.0, height:150.0}, 0.0, 4, 5.0, Color::BLACK);
+                    d.draw_text(&format!("{}", ground_red), 435, 305, 50, Color::BLACK);
+                    d.draw_text(&format!("{}", ground_green), 560, 305, 50, Color::BLACK);
+                    d.draw_text(&format!("{}", ground_blue), 685, 305, 50, Color::BLACK);
 
-                d.draw_rectangle(450, red_ground_slider_pos as i32, 50, 50, Color::WHITE);
-                d.draw_rectangle(575, green_ground_slider_pos as i32, 50, 50, Color::WHITE);
-                d.draw_rectangle(700, blue_ground_slider_pos as i32, 50, 50, Color::WHITE);
+                    d.draw_rectangle(300, 20, 75, 50, cc_1001);
+                    d.draw_rectangle(300, 300, 75, 50, cc_1002);
 
-                d.draw_rectangle_rounded_lines_ex(Rectangle {x: 450.0, y: red_ground_slider_pos as f32, width:50.0, height:50.0}, 0.0, 4, 5.0, Color::BLACK);
-                d.draw_rectangle_rounded_lines_ex(Rectangle {x: 575.0, y: green_ground_slider_pos as f32, width:50.0, height:50.0}, 0.0, 4, 5.0, Color::BLACK);
-                d.draw_rectangle_rounded_lines_ex(Rectangle {x: 700.0, y: blue_ground_slider_pos as f32, width:50.0, height:50.0}, 0.0, 4, 5.0, Color::BLACK);
+                    set_level_type_normal.draw(false, None, 1.0, false, &mut d);
+                    set_level_type_plat.draw(false, None, 1.0, false, &mut d);
 
-                d.draw_text(&format!("{}", ground_red), 435, 305, 50, Color::BLACK);
-                d.draw_text(&format!("{}", ground_green), 560, 305, 50, Color::BLACK);
-                d.draw_text(&format!("{}", ground_blue), 685, 305, 50, Color::BLACK);
+                    // The color channel editor
+                    d.draw_text("Color Channels", 20, 90, 20, Color::WHITE);
 
-                d.draw_rectangle(300, 20, 75, 50, cc_1001);
-                d.draw_rectangle(300, 300, 75, 50, cc_1002);
+                    let channel_text = format!("Channel {}", channel_editor_channel);
+                    d.draw_text(&channel_text, 155 - d.measure_text(&channel_text, 20) / 2, 130, 20, Color::WHITE);
+                    channel_prev_button.draw(false, None, 1.0, false, &mut d);
+                    channel_next_button.draw(false, None, 1.0, false, &mut d);
 
-                set_level_type_normal.draw(false, None, 1.0, false, &mut d);
-                set_level_type_plat.draw(false, None, 1.0, false, &mut d);
+                    match level_color_channels.get(&channel_editor_channel) {
+                        Some((r, g, b)) => d.draw_rectangle(20, 170, 270, 30, Color { r: *r, g: *g, b: *b, a: 255 }),
+                        None => d.draw_text("Not used", 20, 175, 20, Color::GRAY)
+                    }
+                    d.draw_rectangle_rounded_lines_ex(Rectangle { x: 20.0, y: 170.0, width: 270.0, height: 30.0 }, 0.0, 4, 3.0, Color::BLACK);
+
+                    for (textbox, text) in channel_color_textboxes.iter().zip(&channel_color_text) {
+                        textbox.draw(text.clone(), &mut d);
+                    }
+
+                    channel_set_button.draw(false, None, 1.0, false, &mut d);
+                    channel_remove_button.draw(false, None, 1.0, false, &mut d);
+
+                    if let Some(error) = &channel_error {
+                        d.draw_text(error, 20, 300, 15, Color::RED);
+                    }
+
+                    // Every channel the level uses with its color
+                    let used_channels: Vec<(&u16, &(u8, u8, u8))> = level_color_channels.iter().collect();
+                    for (index, (channel, (r, g, b))) in used_channels.iter().take(14).enumerate() {
+                        let x = 20 + (index % 2) as i32 * 135;
+                        let y = 325 + (index / 2) as i32 * 20;
+
+                        d.draw_rectangle(x, y, 15, 15, Color { r: *r, g: *g, b: *b, a: 255 });
+                        d.draw_text(&format!("c{}", channel), x + 20, y, 15, Color::WHITE);
+                    }
+
+                    if used_channels.len() > 14 {
+                        d.draw_text(&format!("And {} more", used_channels.len() - 14), 20, 465, 15, Color::WHITE);
+                    }
+                }
             }
             GameState::LevelSelect => {
                 d.clear_background(Color::BLACK);
@@ -2818,6 +3163,28 @@ async fn main() {
                     20,
                     Color::WHITE
                 );
+
+                d.draw_text(
+                    "Press R to watch your replay",
+                    20,
+                    50,
+                    20,
+                    Color::WHITE
+                );
+
+                if let Some(error) = &replay_error {
+                    d.draw_text(
+                        error,
+                        d.get_screen_width() / 2 - d.measure_text(error, 20) / 2,
+                        d.get_screen_height() - 60,
+                        20,
+                        Color::RED
+                    );
+                }
+
+                if let Some(error) = &level_load_error {
+                    draw_level_error(&mut d, error, None);
+                }
             }
             GameState::LevelComplete => {
                 d.clear_background(Color::WHITE);
@@ -2908,6 +3275,22 @@ async fn main() {
                     Color::WHITE
                 );
 
+                d.draw_text(
+                    "Use Ctrl+Z to undo and Ctrl+Y to redo!",
+                    d.get_screen_width() / 2 - d.measure_text("Use Ctrl+Z to undo and Ctrl+Y to redo!", 30) / 2,
+                    600 - editor_guide_scroll as i32,
+                    30,
+                    Color::WHITE
+                );
+
+                d.draw_text(
+                    "Use Ctrl+C and Ctrl+V to copy and paste and Ctrl+D to duplicate!",
+                    d.get_screen_width() / 2 - d.measure_text("Use Ctrl+C and Ctrl+V to copy and paste and Ctrl+D to duplicate!", 22) / 2,
+                    650 - editor_guide_scroll as i32,
+                    22,
+                    Color::WHITE
+                );
+
                 menu_button.draw(false, None, 1.0, false, &mut d);
             }
             GameState::AccountPage => {
@@ -2962,6 +3345,14 @@ async fn main() {
                     50,
                     Color::WHITE
                 );
+
+                d.draw_text(
+                    &level_verify_result,
+                    d.get_screen_width() / 2 - d.measure_text(&level_verify_result, 20) / 2,
+                    160,
+                    20,
+                    Color::RED
+                );
             }
             GameState::LevelPage => {
                 d.clear_background(Color::BLACK);
@@ -3035,6 +3426,10 @@ async fn main() {
                 if is_mod {
                     level_rate_button.draw(false, None, 1.0, false, &mut d);
                 }
+
+                if let Some(error) = &level_load_error {
+                    draw_level_error(&mut d, error, None);
+                }
             }
             GameState::SearchPage => {
                 d.clear_background(Color::BLACK);
@@ -3052,6 +3447,10 @@ async fn main() {
                 download_level_button.draw(false, None, 1.0, false, &mut d);
                 level_id_textbox.draw(level_id.clone(), &mut d);
                 menu_button.draw(false, None, 1.0, false, &mut d);
+
+                if let Some(error) = &level_load_error {
+                    draw_level_error(&mut d, error, None);
+                }
             }
             GameState::LevelRate => {
                 d.clear_background(Color::BLACK);
@@ -3084,13 +3483,84 @@ async fn main() {
 
                 legacy_grnd_bg_toggle.draw(false, None, 1.0, false, &mut d);
                 game_over_screen_toggle.draw(false, None, 1.0, false, &mut d);
+                smooth_movement_toggle.draw(false, None, 1.0, false, &mut d);
+            }
+            GameState::PlayerCustomization => {
+                d.clear_background(Color { r:0, g:0, b:50, a:255 });
+
+                menu_button.draw(false, None, 1.0, false, &mut d);
+
+                d.draw_text("Customize", d.get_screen_width() / 2 - d.measure_text("Customize", 40) / 2, 30, 40, Color::WHITE);
+
+                // What the player looks like in every gamemode
+                for (index, gamemode) in GAMEMODES.iter().enumerate() {
+                    draw_player(
+                        &mut d,
+                        &player_icons,
+                        &save_data.player,
+                        *gamemode,
+                        Rectangle::new(250.0 + 100.0 * index as f32, 115.0, 50.0, 50.0),
+                        0.0,
+                        cc_1003
+                    );
+
+                    customize_gamemode_buttons[index].draw(false, None, 1.0, *gamemode != customize_gamemode, &mut d);
+                }
+
+                // Every icon there is for the gamemode that's picked
+                let icons = &player_icons[player::gamemode_index(customize_gamemode)];
+                for (index, icon) in icons.iter().enumerate() {
+                    let rect = icon_choice_rect(index);
+                    let mut look = save_data.player.clone();
+                    look.set_icon(customize_gamemode, icon.icon);
+
+                    d.draw_rectangle_rec(rect, Color { r:30, g:30, b:30, a:255 });
+                    draw_player(
+                        &mut d,
+                        &player_icons,
+                        &look,
+                        customize_gamemode,
+                        Rectangle::new(rect.x + rect.width / 2.0, rect.y + rect.height / 2.0, 44.0, 44.0),
+                        0.0,
+                        cc_1003
+                    );
+
+                    if icon.icon == save_data.player.icon(customize_gamemode) {
+                        d.draw_rectangle_lines_ex(rect, 3.0, Color::LIME);
+                    }
+                }
+
+                if icons.is_empty() {
+                    d.draw_text("No icons found", 100, 240, 20, Color::GRAY);
+                }
+
+                d.draw_text("Primary Color", 100, 375, 20, Color::WHITE);
+                d.draw_text("Secondary Color", 100, 455, 20, Color::WHITE);
+
+                for (index, color) in PLAYER_COLORS.iter().enumerate() {
+                    for (y, picked) in [(400.0, save_data.player.primary), (480.0, save_data.player.secondary)] {
+                        let rect = color_choice_rect(index, y);
+                        d.draw_rectangle_rec(rect, rgb_color(*color));
+
+                        if *color == picked {
+                            d.draw_rectangle_lines_ex(rect, 3.0, Color::LIME);
+                        } else {
+                            d.draw_rectangle_lines_ex(rect, 1.0, Color::GRAY);
+                        }
+                    }
+                }
             }
         }
     }
 
+    if quit_requested.load(std::sync::atomic::Ordering::SeqCst) {
+        println!("Got asked to quit, saving...");
+    }
+
     // Saving the level your editing and saving your stars and such
-    if game_state == GameState::Editor {
-        level_string = get_level_text(
+    // A level that failed to load isnt saved over unless you click save yourself
+    if game_state == GameState::Editor && level_load_error.is_none() {
+        let new_level_string = get_level_text(
             current_mode.as_str(),
             current_song,
             bg_red,
@@ -3099,44 +3569,21 @@ async fn main() {
             ground_red as u8,
             ground_green as u8,
             ground_blue as u8,
+            &level_color_channels,
+            &level_info,
             &object_grid
         );
+        level_string = stamp_level_text(new_level_string, &level_string, &mut level_info, unix_time());
 
-        let write_result = fs::write("./save-data/levels/level.txt", level_string);
-
-        println!("{:?}", write_result);
-    }
-
-    let mut save_string = format!(
-        "stars:{};user:{};pass:{};;;",
-
-        stars,
-        user,
-        pass
-    );
-
-    let mut saving_index: u8 = 0;
-    for completed in levels_completed_vec {
-        if completed {
-            save_string.push_str(&format!("{}:1;", saving_index));
-        } else {
-            save_string.push_str(&format!("{}:0;", saving_index));
+        if let Err(error) = write_file_atomically(&open_level_path, &level_string) {
+            println!("Failed to save the level: {}", error);
         }
-
-        saving_index += 1
     }
 
-    save_string.push_str(";;0;");
-    for id in online_levels_beaten.iter().skip(1) {
-        save_string.push_str(&format!("{};", id));
+    if let Err(error) = write_save(std::path::Path::new(SAVE_PATH), &save_data) {
+        println!("Failed to save your progress: {}", error);
     }
 
-    save_string.pop();
-
-    let write_save_result = fs::write("./save-data/save.txt", save_string);
-
-    println!("{:?}", write_save_result);
-
     // Print statements to make unused variable warnings go away because rust is stupid
     println!("{:?}", cc_1001);
     println!("{:?}", cc_1002);
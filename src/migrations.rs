@@ -0,0 +1,172 @@
+use crate::types::Level;
+
+// Every level version from oldest to newest
+// The last one is what the editor saves levels as
+pub const LEVEL_VERSIONS: [&str; 7] = ["ALPHA", "BETA", "1.3", "1.4", "1.5", "1.6", "1.7"];
+pub const CURRENT_LEVEL_VERSION: &str = "1.7";
+
+// Each step upgrades a level from one version to the next one
+// MIGRATIONS[0] turns an ALPHA level into a BETA level and so on
+const MIGRATIONS: [fn(&mut Level); 6] = [
+    alpha_to_beta,
+    beta_to_1_3,
+    v1_3_to_1_4,
+    v1_4_to_1_5,
+    v1_5_to_1_6,
+    v1_6_to_1_7
+];
+
+// There are no ALPHA levels left to check against
+// So this goes off of them using the same y:x:rot:id objects as BETA
+fn alpha_to_beta(level: &mut Level) {
+    level.version = "BETA".to_string();
+}
+
+// 1.3 added no touch and hide to objects
+// BETA objects already get loaded with both of them off so only the version changes
+fn beta_to_1_3(level: &mut Level) {
+    level.version = "1.3".to_string();
+}
+
+// Nothing in the level string changed in 1.4 or 1.5
+fn v1_3_to_1_4(level: &mut Level) {
+    level.version = "1.4".to_string();
+}
+
+fn v1_4_to_1_5(level: &mut Level) {
+    level.version = "1.5".to_string();
+}
+
+// 1.6 levels always say what mode they are
+// Everything before platformer was a normal level
+fn v1_5_to_1_6(level: &mut Level) {
+    level.version = "1.6".to_string();
+
+    if level.mode.is_none() {
+        level.mode = Some("1".to_string());
+    }
+}
+
+// 1.6 put an object's color channel right after its own properties
+// 1.7 gives it its own field after the id so adding a property to an object doesn't move it
+// This has to go off of how many properties objects had in 1.6 and only color triggers had any
+fn v1_6_to_1_7(level: &mut Level) {
+    level.version = "1.7".to_string();
+
+    for object in &mut level.objects {
+        let property_count = if object.id == 23 { 7 } else { 0 };
+
+        if let Some(properties) = object.properties.as_mut() {
+            if properties.len() > property_count {
+                object.color_channel = properties[property_count].parse().unwrap_or(0);
+                properties.truncate(property_count);
+            }
+
+            if properties.is_empty() {
+                object.properties = None;
+            }
+        }
+    }
+}
+
+// Runs every migration between the version of the level and the current version
+pub fn upgrade_level(mut level: Level) -> Level {
+    let start = LEVEL_VERSIONS
+        .iter()
+        .position(|version| *version == level.version)
+        .unwrap_or(MIGRATIONS.len());
+
+    for migration in &MIGRATIONS[start..] {
+        migration(&mut level);
+    }
+
+    level
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // save-data/main-levels/0.txt is a BETA level
+    const BETA_LEVEL: &str = include_str!("../save-data/main-levels/0.txt");
+    // The same level saved as ALPHA
+    const ALPHA_LEVEL: &str = include_str!("../tests/golden/0-ALPHA.txt");
+
+    // Runs one migration on a level string and gives back the new level string
+    fn migrate_once(level_data: &str, step: usize) -> String {
+        let mut level = Level::parse(level_data).unwrap();
+        MIGRATIONS[step](&mut level);
+        level.to_string()
+    }
+
+    #[test]
+    fn alpha_to_beta() {
+        assert_eq!(migrate_once(ALPHA_LEVEL, 0), BETA_LEVEL);
+    }
+
+    #[test]
+    fn beta_to_1_3() {
+        assert_eq!(migrate_once(BETA_LEVEL, 1), include_str!("../tests/golden/0-1.3.txt"));
+    }
+
+    #[test]
+    fn v1_3_to_1_4() {
+        let level = include_str!("../tests/golden/0-1.3.txt");
+
+        assert_eq!(migrate_once(level, 2), include_str!("../tests/golden/0-1.4.txt"));
+    }
+
+    #[test]
+    fn v1_4_to_1_5() {
+        let level = include_str!("../tests/golden/0-1.4.txt");
+
+        assert_eq!(migrate_once(level, 3), include_str!("../tests/golden/0-1.5.txt"));
+    }
+
+    #[test]
+    fn v1_5_to_1_6() {
+        let level = include_str!("../tests/golden/0-1.5.txt");
+
+        assert_eq!(migrate_once(level, 4), include_str!("../tests/golden/0-1.6.txt"));
+    }
+
+    #[test]
+    fn v1_6_to_1_7() {
+        let level = include_str!("../tests/golden/0-1.6.txt");
+
+        assert_eq!(migrate_once(level, 5), include_str!("../tests/golden/0-1.7.txt"));
+    }
+
+    #[test]
+    fn color_channels_get_their_own_field() {
+        let level = "version:1.6;mode:1;;;480:480:0:0:0:1:12;480:520:0:0:0:23:255:0:0:3:0:0:4:20;480:560:0:0:0:23:255:0:0:1;480:600:0:0:0:2";
+
+        assert_eq!(
+            migrate_once(level, 5),
+            "version:1.7;mode:1;;;480:480:0:0:0:1:12;480:520:0:0:0:23:20:255:0:0:3:0:0:4;480:560:0:0:0:23:0:255:0:0:1;480:600:0:0:0:2:0"
+        );
+    }
+
+    #[test]
+    fn upgrade_beta_level() {
+        let level = upgrade_level(Level::parse(BETA_LEVEL).unwrap());
+
+        assert_eq!(level.version, CURRENT_LEVEL_VERSION);
+        assert_eq!(level.to_string(), include_str!("../tests/golden/0-1.7.txt"));
+    }
+
+    #[test]
+    fn upgrade_alpha_level() {
+        let level = upgrade_level(Level::parse(ALPHA_LEVEL).unwrap());
+
+        assert_eq!(level.version, CURRENT_LEVEL_VERSION);
+        assert_eq!(level.to_string(), include_str!("../tests/golden/0-1.7.txt"));
+    }
+
+    #[test]
+    fn upgrade_current_level_does_nothing() {
+        let level_data = include_str!("../tests/golden/0-1.7.txt");
+
+        assert_eq!(upgrade_level(Level::parse(level_data).unwrap()).to_string(), level_data);
+    }
+}
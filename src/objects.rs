@@ -0,0 +1,630 @@
+use std::fs;
+
+use crate::physics::Rect;
+use crate::colors::MAX_COLOR_CHANNEL;
+use crate::types::GameMode;
+
+// This is the list of every object in the game
+// The editor, the renderer, the physics and the level checker all get what they need to know about an object from here
+// To add an object just add it to ObjectRegistry::new and put its texture in Resources/
+
+// Custom objects get loaded from folders in here when the game starts
+// Every folder has an object.txt in it that looks like this:
+// id:1000
+// name:crystal spike
+// texture:crystal-spike.png
+// behavior:hazard
+// hitbox:20,20,10,20
+pub const MODS_PATH: &str = "Resources/objects";
+
+// What an object does when the player touches it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Behavior {
+    // Doesn't do anything
+    Decoration,
+    // Kills the player
+    Hazard,
+    // Can be landed on and kills you if you run into the side
+    Solid,
+    JumpPad { velocity: f32 },
+    GravityPad { velocity: f32 },
+    JumpOrb { velocity: f32 },
+    GravityOrb { velocity: f32 },
+    DeathOrb,
+    GravityPortal { upside_down: bool },
+    GamemodePortal(GameMode),
+    SpeedPortal { multiplier: f32 },
+    EndTrigger,
+    ColorTrigger
+}
+
+// The tabs in the editor's object palette
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ObjectCategory {
+    Blocks,
+    Hazards,
+    OrbsAndPads,
+    Portals,
+    Triggers,
+    Decoration
+}
+
+impl ObjectCategory {
+    // In the order the tabs are shown
+    pub const ALL: [ObjectCategory; 6] = [
+        ObjectCategory::Blocks,
+        ObjectCategory::Hazards,
+        ObjectCategory::OrbsAndPads,
+        ObjectCategory::Portals,
+        ObjectCategory::Triggers,
+        ObjectCategory::Decoration
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ObjectCategory::Blocks => "Blocks",
+            ObjectCategory::Hazards => "Hazards",
+            ObjectCategory::OrbsAndPads => "Orbs/Pads",
+            ObjectCategory::Portals => "Portals",
+            ObjectCategory::Triggers => "Triggers",
+            ObjectCategory::Decoration => "Deco"
+        }
+    }
+}
+
+impl Behavior {
+    // Objects get sorted into the palette tabs by what they do so custom objects end up in the right place too
+    pub fn category(&self) -> ObjectCategory {
+        match self {
+            Behavior::Solid => ObjectCategory::Blocks,
+            Behavior::Hazard | Behavior::DeathOrb => ObjectCategory::Hazards,
+            Behavior::JumpPad { .. }
+            | Behavior::GravityPad { .. }
+            | Behavior::JumpOrb { .. }
+            | Behavior::GravityOrb { .. } => ObjectCategory::OrbsAndPads,
+            Behavior::GravityPortal { .. }
+            | Behavior::GamemodePortal(_)
+            | Behavior::SpeedPortal { .. } => ObjectCategory::Portals,
+            Behavior::EndTrigger | Behavior::ColorTrigger => ObjectCategory::Triggers,
+            Behavior::Decoration => ObjectCategory::Decoration
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectType {
+    pub id: u32,
+    pub name: String,
+    pub texture: String,
+    // How much the texture gets scaled down when it's drawn
+    pub draw_scale: f32,
+    // Speed portals are drawn a bit to the right of where they are placed
+    pub draw_offset_x: f32,
+    // Triggers only show up in the editor and when playtesting
+    pub editor_only: bool,
+    // Hitboxes are relative to the top left of the object
+    // Sideways is used when the object is rotated 90 degrees and upside down when it's rotated more than 145 degrees
+    pub hitbox: Option<Rect>,
+    pub sideways_hitbox: Option<Rect>,
+    pub upside_down_hitbox: Option<Rect>,
+    // The extra values an object has after its id in the level string
+    // The editor uses these to know what to show in the object settings
+    pub properties: Vec<PropertyField>,
+    pub behavior: Behavior
+}
+
+// What kind of value a property holds
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyKind {
+    Number { min: i64, max: i64 },
+    Decimal { min: f32, max: f32 },
+    // One of a few values with the name shown for each in the editor
+    Choice(Vec<(String, String)>)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyField {
+    pub name: String,
+    pub kind: PropertyKind,
+    // What a new object gets when it's placed in the editor
+    pub default: String
+}
+
+impl PropertyField {
+    pub fn number(name: &str, min: i64, max: i64, default: i64) -> PropertyField {
+        PropertyField {
+            name: name.to_string(),
+            kind: PropertyKind::Number { min, max },
+            default: default.to_string()
+        }
+    }
+
+    pub fn decimal(name: &str, min: f32, max: f32, default: &str) -> PropertyField {
+        PropertyField {
+            name: name.to_string(),
+            kind: PropertyKind::Decimal { min, max },
+            default: default.to_string()
+        }
+    }
+
+    pub fn choice(name: &str, choices: &[(&str, &str)], default: &str) -> PropertyField {
+        PropertyField {
+            name: name.to_string(),
+            kind: PropertyKind::Choice(choices.iter().map(|(value, label)| (value.to_string(), label.to_string())).collect()),
+            default: default.to_string()
+        }
+    }
+}
+
+// Solid objects have a few thin hitboxes instead of one big one
+// They are made from the object's hitbox and are relative to the top left of the object like it is
+// A normal 40x40 block gets the same ones blocks have always had
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolidHitboxes {
+    // In normal levels running into the left side kills you
+    pub kill_side: Rect,
+    // In platformer the sides push you out instead
+    pub left_side: Rect,
+    pub right_side: Rect,
+    pub top: Rect,
+    pub bottom: Rect,
+    // Walking off the end of a block makes you start falling
+    pub edge: Rect
+}
+
+impl SolidHitboxes {
+    pub fn new(hitbox: Rect) -> SolidHitboxes {
+        SolidHitboxes {
+            kill_side: Rect::new(hitbox.x, hitbox.y + 10.0, 3.0, hitbox.height - 20.0),
+            left_side: Rect::new(hitbox.x, hitbox.y + hitbox.height / 2.0, 3.0, 3.0),
+            right_side: Rect::new(hitbox.x + hitbox.width, hitbox.y + hitbox.height / 2.0, 3.0, 3.0),
+            top: Rect::new(hitbox.x + 3.0, hitbox.y + 1.0, hitbox.width - 3.0, 3.0),
+            bottom: Rect::new(hitbox.x + 3.0, hitbox.y + hitbox.height - 2.0, hitbox.width - 3.0, 3.0),
+            edge: Rect::new(hitbox.x + hitbox.width * 2.0, hitbox.y + 10.0, 3.0, hitbox.height - 20.0)
+        }
+    }
+}
+
+const FULL_BLOCK: Rect = Rect { x: 0.0, y: 0.0, width: 40.0, height: 40.0 };
+const PORTAL: Rect = Rect { x: 10.0, y: -11.0, width: 20.0, height: 80.0 };
+const SIDEWAYS_PORTAL: Rect = Rect { x: -20.0, y: 11.0, width: 80.0, height: 20.0 };
+
+impl ObjectType {
+    // Most objects are drawn at 0.05 scale with one hitbox
+    fn new(id: u32, name: &str, texture: &str, hitbox: Option<Rect>, behavior: Behavior) -> ObjectType {
+        ObjectType {
+            id,
+            name: name.to_string(),
+            texture: texture.to_string(),
+            draw_scale: 0.05,
+            draw_offset_x: 0.0,
+            editor_only: false,
+            hitbox,
+            sideways_hitbox: None,
+            upside_down_hitbox: None,
+            properties: vec![],
+            behavior
+        }
+    }
+
+    // What a new object gets in its properties when it's placed in the editor
+    pub fn default_properties(&self) -> Vec<String> {
+        self.properties.iter().map(|property| property.default.clone()).collect()
+    }
+
+    fn block(id: u32, name: &str, texture: &str) -> ObjectType {
+        ObjectType::new(id, name, texture, Some(FULL_BLOCK), Behavior::Solid)
+    }
+
+    fn portal(id: u32, name: &str, texture: &str, behavior: Behavior) -> ObjectType {
+        ObjectType {
+            sideways_hitbox: Some(SIDEWAYS_PORTAL),
+            ..ObjectType::new(id, name, texture, Some(PORTAL), behavior)
+        }
+    }
+
+    fn speed_portal(id: u32, name: &str, texture: &str, multiplier: f32) -> ObjectType {
+        ObjectType {
+            draw_scale: 0.1,
+            draw_offset_x: 10.0,
+            ..ObjectType::portal(id, name, texture, Behavior::SpeedPortal { multiplier })
+        }
+    }
+
+    // The hitbox the object has at a rotation
+    pub fn hitbox_for(&self, rotation: i16) -> Option<Rect> {
+        if rotation != 0 && rotation != 180 && rotation != -180 {
+            if let Some(hitbox) = self.sideways_hitbox {
+                return Some(hitbox);
+            }
+        }
+
+        if rotation > 145 || rotation < -145 {
+            if let Some(hitbox) = self.upside_down_hitbox {
+                return Some(hitbox);
+            }
+        }
+
+        self.hitbox
+    }
+
+    // Every hitbox the player can touch on this object
+    // Used for showing hitboxes in debug mode
+    pub fn hitboxes(&self, rotation: i16, current_mode: &str) -> Vec<Rect> {
+        if self.behavior == Behavior::Solid {
+            let solid = match self.hitbox_for(rotation) {
+                Some(hitbox) => SolidHitboxes::new(hitbox),
+                None => return vec![]
+            };
+
+            if current_mode == "1" {
+                vec![solid.kill_side, solid.top, solid.bottom, solid.edge]
+            } else {
+                vec![solid.left_side, solid.right_side, solid.top, solid.bottom, solid.edge]
+            }
+        } else {
+            self.hitbox_for(rotation).into_iter().collect()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectRegistry {
+    pub types: Vec<ObjectType>
+}
+
+impl ObjectRegistry {
+    // All the objects that come with the game
+    // Ids can't ever change or old levels will break
+    pub fn new() -> ObjectRegistry {
+        ObjectRegistry {
+            types: vec![
+                ObjectType::new(0, "null", "Resources/null.png", None, Behavior::Decoration),
+                ObjectType::new(1, "spike", "Resources/spike.png", Some(Rect::new(20.0, 20.0, 10.0, 20.0)), Behavior::Hazard),
+                ObjectType::block(2, "block", "Resources/block.png"),
+                ObjectType::new(3, "pad", "Resources/pad.png", Some(Rect::new(0.0, 35.0, 40.0, 5.0)), Behavior::JumpPad { velocity: 15.0 }),
+                ObjectType::new(4, "orb", "Resources/orb.png", Some(Rect::new(-10.0, -10.0, 60.0, 60.0)), Behavior::JumpOrb { velocity: 13.0 }),
+                ObjectType::portal(5, "upside down", "Resources/upside-down-portal.png", Behavior::GravityPortal { upside_down: true }),
+                ObjectType::portal(6, "right side up", "Resources/right-side-up-portal.png", Behavior::GravityPortal { upside_down: false }),
+                ObjectType {
+                    upside_down_hitbox: Some(Rect::new(20.0, 5.0, 10.0, 10.0)),
+                    ..ObjectType::new(7, "short spike", "Resources/short-spike.png", Some(Rect::new(20.0, 25.0, 10.0, 10.0)), Behavior::Hazard)
+                },
+                ObjectType::portal(8, "cube portal", "Resources/gamemode-portals/cube-portal.png", Behavior::GamemodePortal(GameMode::Cube)),
+                ObjectType::portal(9, "ship portal", "Resources/gamemode-portals/ship-portal.png", Behavior::GamemodePortal(GameMode::Ship)),
+                ObjectType::block(10, "outline block", "Resources/blocks/outline-block.png"),
+                ObjectType::block(11, "outline block 2", "Resources/blocks/outline-block-variant1.png"),
+                ObjectType::block(12, "outline block 3", "Resources/blocks/outline-block-variant2.png"),
+                ObjectType::block(13, "outline block 4", "Resources/blocks/outline-block-variant3.png"),
+                ObjectType::block(14, "outline block 5", "Resources/blocks/outline-block-variant4.png"),
+                ObjectType {
+                    editor_only: true,
+                    ..ObjectType::new(15, "end trigger", "Resources/end-trigger.png", Some(FULL_BLOCK), Behavior::EndTrigger)
+                },
+                // The black block never had a hitbox
+                ObjectType::new(16, "black block", "Resources/blocks/black-block.png", None, Behavior::Decoration),
+                ObjectType::speed_portal(17, "1x speed", "Resources/speed/1x.png", 1.0),
+                ObjectType::speed_portal(18, "2x speed", "Resources/speed/2x.png", 1.4),
+                ObjectType::speed_portal(19, "3x speed", "Resources/speed/3x.png", 1.8),
+                ObjectType::speed_portal(20, "0.5x speed", "Resources/speed/05x.png", 0.8),
+                ObjectType::new(21, "gravity pad", "Resources/gravity-pad.png", Some(Rect::new(0.0, 35.0, 40.0, 5.0)), Behavior::GravityPad { velocity: 7.0 }),
+                ObjectType::new(22, "gravity orb", "Resources/gravity-orb.png", Some(Rect::new(-10.0, -10.0, 60.0, 60.0)), Behavior::GravityOrb { velocity: 7.0 }),
+                ObjectType {
+                    editor_only: true,
+                    properties: vec![
+                        PropertyField::number("Red", 0, 255, 50),
+                        PropertyField::number("Green", 0, 255, 50),
+                        PropertyField::number("Blue", 0, 255, 50),
+                        PropertyField::choice("Changes", &[("1", "Background"), ("2", "Ground"), ("3", "Channel")], "1"),
+                        // Older triggers stop here and change instantly
+                        PropertyField::decimal("Fade Time", 0.0, 60.0, "0"),
+                        PropertyField::choice("Easing", &[("0", "Linear"), ("1", "Ease")], "0"),
+                        // Only used when it changes a channel
+                        PropertyField::number("Target Channel", 1, MAX_COLOR_CHANNEL as i64, 1)
+                    ],
+                    ..ObjectType::new(23, "color trigger", "Resources/color.png", Some(FULL_BLOCK), Behavior::ColorTrigger)
+                },
+                ObjectType::portal(24, "ball portal", "Resources/gamemode-portals/ball-portal.png", Behavior::GamemodePortal(GameMode::Ball)),
+                ObjectType::portal(25, "wave portal", "Resources/gamemode-portals/wave-portal.png", Behavior::GamemodePortal(GameMode::Wave)),
+                ObjectType::new(26, "death orb", "Resources/death-orb.png", Some(Rect::new(-10.0, -10.0, 60.0, 60.0)), Behavior::DeathOrb)
+            ]
+        }
+    }
+
+    // Where an object is in the list
+    // Textures are loaded in the same order so this is also the index of its texture
+    pub fn position(&self, id: u32) -> Option<usize> {
+        // Built in objects are stored at the index of their id so this is usually instant
+        if self.types.get(id as usize).map(|object_type| object_type.id) == Some(id) {
+            return Some(id as usize);
+        }
+
+        self.types.iter().position(|object_type| object_type.id == id)
+    }
+
+    pub fn get(&self, id: u32) -> Option<&ObjectType> {
+        self.position(id).map(|index| &self.types[index])
+    }
+
+    // The name of an object for showing to the player
+    pub fn name(&self, id: u32) -> &str {
+        self.get(id).map(|object_type| object_type.name.as_str()).unwrap_or("unknown object")
+    }
+
+    // Id 0 is the null object so it doesn't count as a real one
+    pub fn exists(&self, id: u32) -> bool {
+        id != 0 && self.position(id).is_some()
+    }
+
+    // The ids of the objects that show up in a palette tab
+    // Searching looks through every tab so you don't have to know where an object is to find it
+    pub fn palette(&self, category: ObjectCategory, search: &str) -> Vec<u32> {
+        let search = search.trim().to_lowercase();
+
+        self.types.iter()
+            .filter(|object_type| object_type.id != 0)
+            .filter(|object_type| if search.is_empty() {
+                object_type.behavior.category() == category
+            } else {
+                object_type.name.to_lowercase().contains(&search)
+            })
+            .map(|object_type| object_type.id)
+            .collect()
+    }
+
+    // Adds a custom object as long as its id isn't already taken
+    pub fn add(&mut self, object_type: ObjectType) -> Result<(), String> {
+        if object_type.id == 0 {
+            return Err("id 0 is the null object".to_string());
+        }
+
+        if let Some(existing) = self.get(object_type.id) {
+            return Err(format!("id {} is already used by {}", object_type.id, existing.name));
+        }
+
+        self.types.push(object_type);
+        Ok(())
+    }
+
+    // Loads every custom object in a folder
+    // Objects that are broken get skipped and the problem with them is given back
+    pub fn load_mods(&mut self, path: &str) -> Vec<String> {
+        let mut problems: Vec<String> = vec![];
+
+        // No folder just means no mods are installed
+        let mut folders: Vec<_> = match fs::read_dir(path) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|entry| entry.is_dir())
+                .collect(),
+            Err(_) => return problems
+        };
+
+        // read_dir doesn't give them back in any order so this makes the palette the same every time
+        folders.sort();
+
+        for folder in folders {
+            let folder = folder.to_string_lossy().to_string();
+            let manifest_path = format!("{}/object.txt", folder);
+
+            let object_type = match fs::read_to_string(&manifest_path) {
+                Ok(manifest) => parse_manifest(&manifest, &folder),
+                Err(error) => Err(format!("Failed to read file: {}", error))
+            };
+
+            if let Err(problem) = object_type.and_then(|object_type| self.add(object_type)) {
+                problems.push(format!("{}: {}", manifest_path, problem));
+            }
+        }
+
+        problems
+    }
+
+    // The built in objects plus everything in Resources/objects
+    pub fn with_mods() -> ObjectRegistry {
+        let mut registry = ObjectRegistry::new();
+
+        for problem in registry.load_mods(MODS_PATH) {
+            println!("Skipping custom object {}", problem);
+        }
+
+        registry
+    }
+}
+
+fn parse_hitbox(value: &str) -> Result<Rect, String> {
+    let numbers: Vec<&str> = value.split(',').collect();
+    if numbers.len() != 4 {
+        return Err(format!("needs x,y,width,height but has {} numbers", numbers.len()));
+    }
+
+    let number = |number: &str| number.trim()
+        .parse::<f32>()
+        .map_err(|_| format!("'{}' is not a number", number));
+
+    Ok(Rect::new(number(numbers[0])?, number(numbers[1])?, number(numbers[2])?, number(numbers[3])?))
+}
+
+// Reads an object.txt
+// The texture is relative to the folder the object is in
+pub fn parse_manifest(manifest: &str, folder: &str) -> Result<ObjectType, String> {
+    let mut id = None;
+    let mut name = None;
+    let mut texture = None;
+    let mut behavior = None;
+    let mut hitbox = None;
+    let mut sideways_hitbox = None;
+    let mut scale = None;
+    let mut velocity = None;
+    let mut flips_gravity = false;
+    let mut portal = None;
+    let mut speed = None;
+
+    for line in manifest.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let (key, value) = line.split_once(':').ok_or(format!("'{}' has no ':'", line))?;
+        let invalid = |reason: String| format!("'{}' {}", key, reason);
+        let number = |value: &str| value.parse::<f32>().map_err(|_| invalid(format!("'{}' is not a number", value)));
+
+        match key {
+            "id" => id = Some(value.parse::<u32>().map_err(|_| invalid(format!("'{}' is not an id", value)))?),
+            "name" => name = Some(value.to_string()),
+            "texture" => texture = Some(value.to_string()),
+            "behavior" => behavior = Some(value.to_string()),
+            "hitbox" => hitbox = Some(parse_hitbox(value).map_err(invalid)?),
+            "sideways_hitbox" => sideways_hitbox = Some(parse_hitbox(value).map_err(invalid)?),
+            "scale" => scale = Some(number(value)?),
+            "velocity" => velocity = Some(number(value)?),
+            "gravity" => flips_gravity = value == "1",
+            "portal" => portal = Some(value.to_string()),
+            "speed" => speed = Some(number(value)?),
+            _ => return Err(format!("'{}' isn't something an object can have", key))
+        }
+    }
+
+    let id = id.ok_or("is missing 'id'")?;
+    let name = name.ok_or("is missing 'name'")?;
+    let texture = texture.ok_or("is missing 'texture'")?;
+    let behavior = behavior.ok_or("is missing 'behavior'")?;
+
+    // Every behavior works like the built in object that does the same thing unless the manifest says otherwise
+    let base = match behavior.as_str() {
+        "decoration" => ObjectType::new(id, &name, &texture, None, Behavior::Decoration),
+        "hazard" => ObjectType::new(id, &name, &texture, Some(Rect::new(20.0, 20.0, 10.0, 20.0)), Behavior::Hazard),
+        "solid" => ObjectType::block(id, &name, &texture),
+        "pad" => {
+            let behavior = if flips_gravity {
+                Behavior::GravityPad { velocity: velocity.unwrap_or(7.0) }
+            } else {
+                Behavior::JumpPad { velocity: velocity.unwrap_or(15.0) }
+            };
+            ObjectType::new(id, &name, &texture, Some(Rect::new(0.0, 35.0, 40.0, 5.0)), behavior)
+        }
+        "orb" => {
+            let behavior = if flips_gravity {
+                Behavior::GravityOrb { velocity: velocity.unwrap_or(7.0) }
+            } else {
+                Behavior::JumpOrb { velocity: velocity.unwrap_or(13.0) }
+            };
+            ObjectType::new(id, &name, &texture, Some(Rect::new(-10.0, -10.0, 60.0, 60.0)), behavior)
+        }
+        "portal" => {
+            let behavior = match portal.as_deref() {
+                Some("cube") => Behavior::GamemodePortal(GameMode::Cube),
+                Some("ship") => Behavior::GamemodePortal(GameMode::Ship),
+                Some("ball") => Behavior::GamemodePortal(GameMode::Ball),
+                Some("wave") => Behavior::GamemodePortal(GameMode::Wave),
+                Some("upside-down") => Behavior::GravityPortal { upside_down: true },
+                Some("right-side-up") => Behavior::GravityPortal { upside_down: false },
+                Some("speed") => Behavior::SpeedPortal { multiplier: speed.ok_or("is a speed portal but is missing 'speed'")? },
+                Some(other) => return Err(format!("'portal' '{}' has to be cube, ship, ball, wave, upside-down, right-side-up or speed", other)),
+                None => return Err("is a portal but is missing 'portal'".to_string())
+            };
+            ObjectType::portal(id, &name, &texture, behavior)
+        }
+        other => return Err(format!("'behavior' '{}' has to be decoration, solid, hazard, pad, orb or portal", other))
+    };
+
+    Ok(ObjectType {
+        texture: format!("{}/{}", folder, texture),
+        draw_scale: scale.unwrap_or(base.draw_scale),
+        hitbox: hitbox.or(base.hitbox),
+        sideways_hitbox: sideways_hitbox.or(base.sideways_hitbox),
+        ..base
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_objects_are_stored_at_their_id() {
+        let registry = ObjectRegistry::new();
+
+        for (index, object_type) in registry.types.iter().enumerate() {
+            assert_eq!(object_type.id as usize, index, "{} is in the wrong spot", object_type.name);
+        }
+        assert_eq!(registry.name(23), "color trigger");
+        assert!(!registry.exists(0));
+        assert!(!registry.exists(27));
+    }
+
+    #[test]
+    fn blocks_keep_their_old_hitboxes() {
+        let block = SolidHitboxes::new(FULL_BLOCK);
+
+        assert_eq!(block.kill_side, Rect::new(0.0, 10.0, 3.0, 20.0));
+        assert_eq!(block.left_side, Rect::new(0.0, 20.0, 3.0, 3.0));
+        assert_eq!(block.right_side, Rect::new(40.0, 20.0, 3.0, 3.0));
+        assert_eq!(block.top, Rect::new(3.0, 1.0, 37.0, 3.0));
+        assert_eq!(block.bottom, Rect::new(3.0, 38.0, 37.0, 3.0));
+        assert_eq!(block.edge, Rect::new(80.0, 10.0, 3.0, 20.0));
+
+        // Custom solids get theirs from the hitbox in their manifest
+        let slab = parse_manifest("id:100\nname:slab\ntexture:slab.png\nbehavior:solid\nhitbox:0,20,40,20", "mods/slab").unwrap();
+        assert_eq!(slab.hitboxes(0, "2")[2], Rect::new(3.0, 21.0, 37.0, 3.0));
+    }
+
+    #[test]
+    fn hitboxes_change_with_rotation() {
+        let registry = ObjectRegistry::new();
+        let portal = registry.get(5).unwrap();
+        let short_spike = registry.get(7).unwrap();
+
+        assert_eq!(portal.hitbox_for(180), Some(PORTAL));
+        assert_eq!(portal.hitbox_for(90), Some(SIDEWAYS_PORTAL));
+        assert_eq!(short_spike.hitbox_for(90), short_spike.hitbox);
+        assert_eq!(short_spike.hitbox_for(180), short_spike.upside_down_hitbox);
+    }
+
+    #[test]
+    fn palette_is_split_into_categories() {
+        let registry = ObjectRegistry::new();
+
+        assert_eq!(registry.palette(ObjectCategory::Blocks, ""), vec![2, 10, 11, 12, 13, 14]);
+        assert_eq!(registry.palette(ObjectCategory::Triggers, ""), vec![15, 23]);
+
+        // Every object is in exactly one tab
+        let in_tabs: usize = ObjectCategory::ALL.iter().map(|category| registry.palette(*category, "").len()).sum();
+        assert_eq!(in_tabs, registry.types.len() - 1);
+
+        // Searching ignores the tab and the case
+        assert_eq!(registry.palette(ObjectCategory::Blocks, " Orb"), vec![4, 22, 26]);
+        assert!(registry.palette(ObjectCategory::Blocks, "nothing is called this").is_empty());
+    }
+
+    #[test]
+    fn manifests_are_loaded() {
+        let object_type = parse_manifest(
+            "id:1000\nname:crystal spike\ntexture:crystal.png\nbehavior:hazard\nhitbox:15,20,10,20\n",
+            "Resources/objects/crystal"
+        ).unwrap();
+
+        assert_eq!(object_type.texture, "Resources/objects/crystal/crystal.png");
+        assert_eq!(object_type.behavior, Behavior::Hazard);
+        assert_eq!(object_type.hitbox, Some(Rect::new(15.0, 20.0, 10.0, 20.0)));
+
+        let portal = parse_manifest("id:1001\nname:fast\ntexture:fast.png\nbehavior:portal\nportal:speed\nspeed:2.2", "mods").unwrap();
+        assert_eq!(portal.behavior, Behavior::SpeedPortal { multiplier: 2.2 });
+        assert_eq!(portal.sideways_hitbox, Some(SIDEWAYS_PORTAL));
+    }
+
+    #[test]
+    fn bad_manifests_are_rejected() {
+        assert!(parse_manifest("id:1000\nname:a\ntexture:a.png\nbehavior:bouncy", "mods").is_err());
+        assert!(parse_manifest("id:1000\nname:a\ntexture:a.png\nbehavior:portal", "mods").is_err());
+        assert!(parse_manifest("id:1000\nname:a\nbehavior:solid", "mods").is_err());
+        assert!(parse_manifest("id:1000\nname:a\ntexture:a.png\nbehavior:solid\ncolour:red", "mods").is_err());
+
+        // Mods can't replace built in objects
+        let mut registry = ObjectRegistry::new();
+        let spike = parse_manifest("id:1\nname:a\ntexture:a.png\nbehavior:hazard", "mods").unwrap();
+        assert_eq!(registry.add(spike), Err("id 1 is already used by spike".to_string()));
+    }
+}
@@ -0,0 +1,743 @@
+use crate::objects::{Behavior, ObjectRegistry, SolidHitboxes};
+use crate::colors::{ColorTarget, Easing};
+use crate::spatial::ObjectColumns;
+use crate::types::{GameMode, ObjectStruct};
+
+// Nothing in here uses raylib so levels can be played without a window
+// Every value is per frame and the game runs at 60 fps
+pub const DEFAULT_GRAVITY: f32 = 0.8;
+pub const DEFAULT_JUMP_FORCE: f32 = -13.0;
+pub const DEFAULT_MOVEMENT_SPEED: f32 = 6.0;
+pub const SHIP_POWER: f32 = 0.7;
+pub const SHIP_FALLING_SPEED: f32 = 0.5;
+pub const WAVE_VELOCITY: f32 = 1.1;
+
+// The physics always run at this rate no matter what the frame rate is
+pub const TICKS_PER_SECOND: f32 = 60.0;
+pub const TICK_TIME: f32 = 1.0 / TICKS_PER_SECOND;
+
+// If the game freezes for a bit it slows down instead of running a ton of ticks at once
+const MAX_TICKS_PER_FRAME: u32 = 5;
+
+// Objects only get checked while they are on screen and the window is always 800 wide
+const SCREEN_WIDTH: f32 = 800.0;
+const COLLISION_MARGIN: i32 = 200;
+
+// Same thing as the raylib Rectangle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32
+}
+
+impl Rect {
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> Rect {
+        Rect { x, y, width, height }
+    }
+
+    // Works the same way as the one in raylib
+    pub fn check_collision_recs(&self, other: &Rect) -> bool {
+        self.x < other.x + other.width
+        && self.x + self.width > other.x
+        && self.y < other.y + other.height
+        && self.y + self.height > other.y
+    }
+}
+
+// What the player is holding down this frame
+// Jump is space or the mouse and left and right only do something in platformer
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PhysicsInput {
+    pub jump: bool,
+    pub left: bool,
+    pub right: bool,
+    // Blocks only care about the mouse and not space
+    pub mouse: bool,
+    // If space or the mouse got let go since the last tick
+    pub released: bool
+}
+
+// Things that happened during a frame that the playing state has to deal with
+#[derive(Debug, Clone, PartialEq)]
+pub enum PhysicsEvent {
+    ColorTrigger {
+        red: u8,
+        green: u8,
+        blue: u8,
+        target: ColorTarget,
+        // How many seconds it takes to fade to the new color
+        fade_time: f32,
+        easing: Easing
+    },
+    LevelComplete
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhysicsState {
+    pub player: Rect,
+    pub centered_player: Rect,
+    pub small_player: Rect,
+    pub velocity_y: f32,
+    pub gravity: f32,
+    pub jump_force: f32,
+    pub movement_speed: f32,
+    pub gamemode: GameMode,
+    pub world_offset: f32,
+    pub rotation: f32,
+    pub player_cam_y: i32,
+    pub moving_direction: u8,
+    pub is_on_ground: bool,
+    pub on_orb: bool,
+    pub touching_block_ceiling: bool,
+    // If the player was in a color trigger last tick
+    // Triggers only go off when you first touch them so standing in one doesn't keep restarting the fade
+    pub touching_color_trigger: bool,
+    // If the player is in a color trigger this tick
+    pub in_color_trigger: bool,
+    pub kill_player: bool,
+    // The index of the object that killed the player
+    pub killed_by: Option<usize>,
+    pub events: Vec<PhysicsEvent>
+}
+
+impl PhysicsState {
+    // This is how the player starts every attempt
+    pub fn new() -> PhysicsState {
+        let player = Rect::new(200.0, 500.0, 40.0, 40.0);
+
+        PhysicsState {
+            player,
+            centered_player: player,
+            small_player: player,
+            velocity_y: 0.0,
+            gravity: DEFAULT_GRAVITY,
+            jump_force: DEFAULT_JUMP_FORCE,
+            movement_speed: DEFAULT_MOVEMENT_SPEED,
+            gamemode: GameMode::Cube,
+            world_offset: 0.0,
+            rotation: 0.0,
+            player_cam_y: 0,
+            moving_direction: 0,
+            is_on_ground: true,
+            on_orb: true,
+            touching_block_ceiling: false,
+            touching_color_trigger: false,
+            in_color_trigger: false,
+            kill_player: false,
+            killed_by: None,
+            events: Vec::new()
+        }
+    }
+}
+
+// Keeps track of how much time has passed so physics can run at a fixed rate
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FixedTimestep {
+    accumulator: f32
+}
+
+impl FixedTimestep {
+    pub fn new() -> FixedTimestep {
+        FixedTimestep { accumulator: 0.0 }
+    }
+
+    // Adds the time the last frame took and gives back how many ticks to run this frame
+    pub fn advance(&mut self, delta_time: f32) -> u32 {
+        self.accumulator += delta_time;
+
+        let mut ticks = 0;
+        while self.accumulator >= TICK_TIME && ticks < MAX_TICKS_PER_FRAME {
+            self.accumulator -= TICK_TIME;
+            ticks += 1;
+        }
+
+        // Whatever is left over after the max is just thrown away
+        if ticks == MAX_TICKS_PER_FRAME {
+            self.accumulator = self.accumulator.min(TICK_TIME);
+        }
+
+        ticks
+    }
+
+    // How far it is between the last tick and the next one from 0 to 1
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / TICK_TIME).clamp(0.0, 1.0)
+    }
+}
+
+// Gives back a state that is between two ticks for smoother drawing
+// Only the things that move the player and the camera get blended
+pub fn interpolate(previous: &PhysicsState, current: &PhysicsState, alpha: f32) -> PhysicsState {
+    let lerp = |from: f32, to: f32| from + (to - from) * alpha;
+
+    let mut state = current.clone();
+    state.player.y = lerp(previous.player.y, current.player.y);
+    state.world_offset = lerp(previous.world_offset, current.world_offset);
+    state.player_cam_y = lerp(previous.player_cam_y as f32, current.player_cam_y as f32).round() as i32;
+
+    state
+}
+
+// Runs one tick of the level
+// Check kill_player and events afterwards to see what happened
+pub fn step(
+    state: &mut PhysicsState,
+    input: PhysicsInput,
+    object_grid: &[ObjectStruct],
+    object_columns: &ObjectColumns,
+    current_mode: &str,
+    registry: &ObjectRegistry
+) {
+    state.kill_player = false;
+    state.killed_by = None;
+    state.events.clear();
+    state.in_color_trigger = false;
+
+    physics_handle(state, input, current_mode);
+    update_player_hitboxes(state);
+
+    // Only objects around the screen get checked
+    // Blocks in platformer can push the player while this is going so there is some extra room on both sides
+    let nearby_objects = object_columns.in_range(
+        (-40.0 - state.world_offset).floor() as i32 - COLLISION_MARGIN,
+        (SCREEN_WIDTH - state.world_offset).ceil() as i32 + COLLISION_MARGIN
+    );
+
+    for index in nearby_objects {
+        let object = &object_grid[index];
+        if object.x as f32 + state.world_offset < SCREEN_WIDTH
+        && object.x as f32 + state.world_offset > -40.0
+        && object.no_touch == 0 {
+            hitbox_collision(object, state, input, current_mode, registry);
+
+            if state.kill_player && state.killed_by.is_none() {
+                state.killed_by = Some(index);
+            }
+        }
+    }
+
+    // Orbs only work again after you let go
+    if input.released {
+        state.on_orb = true;
+    }
+
+    // Every color trigger the player touches in the same tick still goes off
+    // After that none of them do until the player is out of all of them
+    state.touching_color_trigger = state.in_color_trigger;
+}
+
+// The hitboxes are made from where the player is at the start of collision checking
+// They don't move if an object moves the player until the next tick
+pub fn update_player_hitboxes(state: &mut PhysicsState) {
+    // Updating the centered player hitbox
+    state.centered_player = Rect::new(
+        state.player.x - state.player.width / 2.0,
+        state.player.y - state.player.height / 2.0,
+        state.player.width,
+        state.player.height
+    );
+
+    // Updating the secondary player hitbox used for blocks
+    state.small_player = Rect::new(
+        state.centered_player.x + 15.0,
+        state.centered_player.y + 10.0,
+        20.0,
+        20.0
+    );
+}
+
+pub fn physics_handle(state: &mut PhysicsState, input: PhysicsInput, current_mode: &str) {
+    if state.gamemode == GameMode::Cube {
+        // This is what handles jumping if your in the cube
+        if state.is_on_ground && input.jump {
+            state.velocity_y = state.jump_force;
+            state.is_on_ground = false;
+        }
+    } else if state.gamemode == GameMode::Ship {
+        // This is what handles flying up and down in the ship
+        // Back before I made the ship I planned on your gravity just changing if your holding
+        // But I didn't go with that because then the ship physics would suck
+        if !state.touching_block_ceiling {
+            if input.jump {
+                if state.gravity > 0.0 {
+                    if state.velocity_y > -10.0 {
+                        state.velocity_y -= SHIP_POWER
+                    }
+                } else {
+                    if state.velocity_y < 10.0 {
+                        state.velocity_y += SHIP_POWER
+                    }
+                }
+            } else {
+                if state.gravity > 0.0 {
+                    if state.velocity_y < 10.0 {
+                        state.velocity_y += SHIP_FALLING_SPEED
+                    }
+                } else {
+                    if state.velocity_y > -10.0 {
+                        state.velocity_y -= SHIP_FALLING_SPEED
+                    }
+                }
+            }
+        } else {
+            state.velocity_y = 0.0
+        }
+    } else if state.gamemode == GameMode::Ball {
+        // This is what handles changing gravity if your in the ball
+        if state.is_on_ground && input.jump {
+            state.gravity = -state.gravity;
+            state.is_on_ground = false;
+        }
+    } else if state.gamemode == GameMode::Wave {
+        if state.gravity > 0.0 {
+            if input.jump {
+                state.velocity_y = -(WAVE_VELOCITY * state.movement_speed)
+            } else {
+                state.velocity_y = WAVE_VELOCITY * state.movement_speed
+            }
+        } else {
+            if input.jump {
+                state.velocity_y = WAVE_VELOCITY * state.movement_speed
+            } else {
+                state.velocity_y = -(WAVE_VELOCITY * state.movement_speed)
+            }
+        }
+    }
+
+    // This handles moving forward and backward
+    if current_mode == "1" {
+        state.world_offset -= state.movement_speed;
+    } else if current_mode == "2" {
+        if input.right {
+            state.world_offset -= state.movement_speed;
+            state.moving_direction = 1
+        } else if input.left {
+            state.world_offset += state.movement_speed;
+            state.moving_direction = 2
+        } else {
+            state.moving_direction = 0
+        }
+    }
+
+    // This handles making the player fall down
+    if (state.gamemode == GameMode::Cube || state.gamemode == GameMode::Ball) && state.velocity_y < 20.0 && state.velocity_y > -20.0 {
+        if state.gamemode == GameMode::Cube {
+            state.velocity_y += state.gravity;
+        } else {
+            state.velocity_y += state.gravity - if state.gravity > 0.0 { 0.2 } else { -0.2 };
+        }
+    }
+    state.player.y += state.velocity_y;
+
+    // This handles the ground logic and the player rotation
+    if state.player.y >= 500.0 - state.player_cam_y as f32 {
+        state.player.y = 500.0 - state.player_cam_y as f32;
+        state.velocity_y = 0.0;
+        state.is_on_ground = true;
+        state.rotation = 0.0;
+    } else {
+        // If in platformer the player only rotates if they are moving
+        // They rotate different directions based on gravity and direction
+        if state.gravity > 0.0 {
+            if state.moving_direction == 1
+            || current_mode == "1" {
+                state.rotation += 5.0;
+            } else if state.moving_direction == 2 {
+                state.rotation -= 5.0;
+            } else {
+                state.rotation = 0.0;
+            }
+        } else {
+            if state.moving_direction == 1
+            || current_mode == "1" {
+                state.rotation -= 5.0;
+            } else if state.moving_direction == 2 {
+                state.rotation += 5.0;
+            } else {
+                state.rotation = 0.0;
+            }
+        }
+    }
+
+    // This handles moving the camera up or down if the player is in a certain spot on the screen
+    if state.player.y >= 501.0 {
+        state.player_cam_y += state.velocity_y as i32;
+        state.player.y = 502.0
+    }
+
+    if state.player.y <= 50.0 {
+        state.player_cam_y += state.velocity_y as i32;
+        state.player.y = 49.0
+    }
+}
+
+// This is the function for checking hitboxes
+// What each object does and where its hitboxes are comes from the object registry
+pub fn hitbox_collision(
+    object: &ObjectStruct,
+    state: &mut PhysicsState,
+    input: PhysicsInput,
+    current_mode: &str,
+    registry: &ObjectRegistry
+) {
+    let centered_player = state.centered_player;
+    let small_player = state.small_player;
+
+    let object_type = match registry.get(object.id) {
+        Some(object_type) => object_type,
+        None => return
+    };
+
+    // Moves a hitbox from being relative to the object to where it is on screen
+    // This uses the world offset from right now since blocks can push the player in platformer
+    let place = |state: &PhysicsState, hitbox: Rect| -> Rect {
+        Rect {
+            x: object.x as f32 + state.world_offset + hitbox.x,
+            y: object.y as f32 + hitbox.y - state.player_cam_y as f32,
+            width: hitbox.width,
+            height: hitbox.height
+        }
+    };
+    let touching = |state: &PhysicsState| -> bool {
+        object_type.hitbox_for(object.rotation)
+            .is_some_and(|hitbox| centered_player.check_collision_recs(&place(state, hitbox)))
+    };
+
+    match object_type.behavior {
+        Behavior::Decoration => {}
+
+        Behavior::Hazard => {
+            state.kill_player |= touching(state);
+        }
+
+        Behavior::Solid => {
+            // Custom solids can have their own hitbox so everything goes off of it instead of a 40x40 block
+            let hitbox = match object_type.hitbox_for(object.rotation) {
+                Some(hitbox) => hitbox,
+                None => return
+            };
+            let solid = SolidHitboxes::new(hitbox);
+
+            if current_mode == "1" {
+                state.kill_player |= small_player.check_collision_recs(&place(state, solid.kill_side));
+            } else if centered_player.check_collision_recs(&place(state, solid.left_side)) {
+                state.world_offset = -(object.x as f32 + hitbox.x - 220.0)
+            } else if centered_player.check_collision_recs(&place(state, solid.right_side)) {
+                state.world_offset = -(object.x as f32 + hitbox.x + hitbox.width - 180.0)
+            }
+
+            let player_cam_y = state.player_cam_y;
+            let top = object.y as f32 + hitbox.y;
+            let bottom = top + hitbox.height;
+
+            if centered_player.check_collision_recs(&place(state, solid.top)) {
+                state.is_on_ground = true;
+                state.rotation = 0.0;
+                if !input.mouse {
+                    state.player.y = top - 19.0 - player_cam_y as f32;
+                    state.velocity_y = 0.0;
+                } else {
+                    if state.gravity < 0.0 {
+                        state.touching_block_ceiling = true;
+                        state.player.y = top - 21.0 - player_cam_y as f32;
+                    }
+                }
+            } else {
+                state.touching_block_ceiling = false;
+            }
+
+            if centered_player.check_collision_recs(&place(state, solid.bottom)) {
+                state.is_on_ground = true;
+                state.rotation = 0.0;
+                if !input.mouse {
+                    state.player.y = bottom + 21.0 - player_cam_y as f32;
+                    state.velocity_y = 0.0;
+                } else {
+                    if state.gravity > 0.0 {
+                        state.touching_block_ceiling = true;
+                        state.player.y = bottom + 21.0 - player_cam_y as f32;
+                    }
+                }
+            } else {
+                state.touching_block_ceiling = false;
+            }
+
+            if centered_player.check_collision_recs(&place(state, solid.edge)) {
+                state.is_on_ground = false;
+            }
+        }
+
+        Behavior::JumpPad { velocity } => {
+            if touching(state) {
+                state.velocity_y = if state.gravity > 0.0 { -velocity } else { velocity };
+                state.is_on_ground = false;
+            }
+        }
+
+        Behavior::GravityPad { velocity } => {
+            if touching(state) {
+                flip_gravity(state, velocity);
+                state.is_on_ground = false;
+            }
+        }
+
+        Behavior::JumpOrb { .. }
+        | Behavior::GravityOrb { .. }
+        | Behavior::DeathOrb => {
+            if touching(state) {
+                if state.on_orb && input.jump {
+                    match object_type.behavior {
+                        Behavior::JumpOrb { velocity } => {
+                            state.velocity_y = if state.gravity > 0.0 { -velocity } else { velocity };
+                        }
+                        Behavior::GravityOrb { velocity } => flip_gravity(state, velocity),
+                        _ => state.kill_player = true
+                    }
+                    state.on_orb = false
+                }
+
+                state.is_on_ground = false
+            }
+        }
+
+        Behavior::GravityPortal { upside_down } => {
+            if touching(state) {
+                if upside_down {
+                    state.jump_force = -DEFAULT_JUMP_FORCE;
+                    state.gravity = -DEFAULT_GRAVITY;
+                } else {
+                    state.jump_force = DEFAULT_JUMP_FORCE;
+                    state.gravity = DEFAULT_GRAVITY;
+                }
+
+                state.is_on_ground = false
+            }
+        }
+
+        Behavior::GamemodePortal(gamemode) => {
+            // There is no wave in platformer
+            if touching(state) && (gamemode != GameMode::Wave || current_mode == "1") {
+                state.gamemode = gamemode;
+                state.is_on_ground = false
+            }
+        }
+
+        Behavior::SpeedPortal { multiplier } => {
+            if touching(state) {
+                state.movement_speed = DEFAULT_MOVEMENT_SPEED * multiplier;
+            }
+        }
+
+        Behavior::EndTrigger => {
+            if touching(state) {
+                state.events.push(PhysicsEvent::LevelComplete);
+            }
+        }
+
+        Behavior::ColorTrigger => {
+            if touching(state) {
+                // load_level already made sure these are all there and are numbers
+                // Triggers from before fades existed only have the first 4 so they change instantly
+                let property = |index: usize| -> &str {
+                    object.properties.as_ref()
+                        .and_then(|properties| properties.get(index))
+                        .map(|property| property.as_str())
+                        .unwrap_or("")
+                };
+                let color = |index: usize| -> u8 { property(index).parse().unwrap_or(0) };
+
+                state.in_color_trigger = true;
+
+                if !state.touching_color_trigger {
+                    if let Some(target) = ColorTarget::from_trigger(color(3), property(6)) {
+                        state.events.push(PhysicsEvent::ColorTrigger {
+                            red: color(0),
+                            green: color(1),
+                            blue: color(2),
+                            target,
+                            fade_time: property(4).parse().unwrap_or(0.0),
+                            easing: Easing::from_property(property(5))
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Gravity pads and orbs flip gravity and push the player the way they are now falling
+fn flip_gravity(state: &mut PhysicsState, velocity: f32) {
+    if state.gravity > 0.0 {
+        state.velocity_y = -velocity;
+        state.gravity = -DEFAULT_GRAVITY;
+        state.jump_force = -DEFAULT_JUMP_FORCE
+    } else {
+        state.velocity_y = velocity;
+        state.gravity = DEFAULT_GRAVITY;
+        state.jump_force = DEFAULT_JUMP_FORCE
+    }
+}
+
+#[cfg(test)]
+mod scenarios;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(x: i32, y: i32, id: u32) -> ObjectStruct {
+        ObjectStruct {
+            y,
+            x,
+            rotation: 0,
+            no_touch: 0,
+            hide: 0,
+            color_channel: 0,
+            id,
+            selected: false,
+            properties: None
+        }
+    }
+
+    #[test]
+    fn timestep_runs_sixty_ticks_a_second() {
+        let mut timestep = FixedTimestep::new();
+        let mut ticks = 0;
+
+        // A slow computer running at 24 fps
+        for _ in 0..24 {
+            ticks += timestep.advance(1.0 / 24.0);
+        }
+        assert!((59..=60).contains(&ticks));
+
+        // A fast one running at 240 fps
+        let mut ticks = 0;
+        for _ in 0..240 {
+            ticks += timestep.advance(1.0 / 240.0);
+        }
+        assert!((59..=61).contains(&ticks));
+    }
+
+    #[test]
+    fn timestep_doesnt_catch_up_forever() {
+        let mut timestep = FixedTimestep::new();
+
+        assert_eq!(timestep.advance(3.0), MAX_TICKS_PER_FRAME);
+        assert!(timestep.advance(0.0) <= 1);
+    }
+
+    #[test]
+    fn cube_jumps_and_lands() {
+        let mut state = PhysicsState::new();
+        let jump = PhysicsInput { jump: true, ..Default::default() };
+
+        step(&mut state, jump, &[], &ObjectColumns::new(&[]), "1", &ObjectRegistry::new());
+        assert!(!state.is_on_ground);
+        assert!(state.player.y < 500.0);
+
+        for _ in 0..60 {
+            step(&mut state, PhysicsInput::default(), &[], &ObjectColumns::new(&[]), "1", &ObjectRegistry::new());
+        }
+        assert!(state.is_on_ground);
+        assert_eq!(state.player.y, 500.0);
+        assert_eq!(state.world_offset, -DEFAULT_MOVEMENT_SPEED * 61.0);
+    }
+
+    #[test]
+    fn running_into_a_spike_kills_the_player() {
+        let mut state = PhysicsState::new();
+        let level = [object(400, 480, 1)];
+        let mut frames = 0;
+
+        while !state.kill_player && frames < 120 {
+            step(&mut state, PhysicsInput::default(), &level, &ObjectColumns::new(&level), "1", &ObjectRegistry::new());
+            frames += 1;
+        }
+        assert!(state.kill_player);
+        assert_eq!(state.killed_by, Some(0));
+    }
+
+    #[test]
+    fn color_triggers_only_go_off_once_while_touched() {
+        let registry = ObjectRegistry::new();
+        let mut trigger = object(200, 480, 23);
+        trigger.properties = Some(vec!["255".to_string(), "0".to_string(), "0".to_string(), "1".to_string(), "2".to_string(), "0".to_string(), "1".to_string()]);
+        // Two triggers in the same spot both go off
+        let level = [trigger.clone(), trigger];
+        let columns = ObjectColumns::new(&level);
+
+        // Standing still in platformer keeps the player in them
+        let mut state = PhysicsState::new();
+        let mut events = 0;
+        for _ in 0..30 {
+            step(&mut state, PhysicsInput::default(), &level, &columns, "2", &registry);
+            events += state.events.len();
+        }
+        assert_eq!(events, 2);
+        assert!(state.touching_color_trigger);
+
+        // Walking out and back in makes them go off again
+        for _ in 0..10 {
+            step(&mut state, PhysicsInput { right: true, ..Default::default() }, &level, &columns, "2", &registry);
+        }
+        assert!(!state.touching_color_trigger);
+
+        let mut events = 0;
+        for _ in 0..10 {
+            step(&mut state, PhysicsInput { left: true, ..Default::default() }, &level, &columns, "2", &registry);
+            events += state.events.len();
+        }
+        assert_eq!(events, 2);
+    }
+
+    #[test]
+    fn orbs_work_again_after_letting_go() {
+        let registry = ObjectRegistry::new();
+        let level = [object(200, 480, 4), object(400, 480, 4)];
+        let columns = ObjectColumns::new(&level);
+        let holding = PhysicsInput { jump: true, mouse: true, ..Default::default() };
+
+        let mut state = PhysicsState::new();
+        step(&mut state, holding, &level, &columns, "1", &registry);
+        assert!(!state.on_orb);
+
+        // Not holding anything doesn't count as letting go
+        step(&mut state, PhysicsInput::default(), &level, &columns, "1", &registry);
+        assert!(!state.on_orb);
+
+        step(&mut state, PhysicsInput { released: true, ..Default::default() }, &level, &columns, "1", &registry);
+        assert!(state.on_orb);
+    }
+
+    #[test]
+    fn holding_space_on_a_block_still_lands() {
+        let registry = ObjectRegistry::new();
+        let level = [object(180, 500, 2)];
+
+        for (input, snapped) in [
+            (PhysicsInput { jump: true, ..Default::default() }, true),
+            (PhysicsInput { jump: true, mouse: true, ..Default::default() }, false)
+        ] {
+            let mut state = PhysicsState::new();
+            state.velocity_y = 3.0;
+            update_player_hitboxes(&mut state);
+            hitbox_collision(&level[0], &mut state, input, "1", &registry);
+
+            assert_eq!(state.velocity_y == 0.0, snapped);
+        }
+    }
+
+    #[test]
+    fn platformer_only_moves_while_holding_a_direction() {
+        let mut state = PhysicsState::new();
+
+        step(&mut state, PhysicsInput::default(), &[], &ObjectColumns::new(&[]), "2", &ObjectRegistry::new());
+        assert_eq!(state.world_offset, 0.0);
+
+        step(&mut state, PhysicsInput { right: true, ..Default::default() }, &[], &ObjectColumns::new(&[]), "2", &ObjectRegistry::new());
+        assert_eq!(state.world_offset, -DEFAULT_MOVEMENT_SPEED);
+        assert_eq!(state.moving_direction, 1);
+    }
+}
@@ -0,0 +1,369 @@
+// Scenario tests for hitbox_collision
+// Each one puts the player somewhere next to a single object, checks collision once and looks at what changed
+// If one of these starts failing after a physics change then the way that object plays has changed too
+
+use super::{
+    hitbox_collision,
+    update_player_hitboxes,
+    PhysicsEvent,
+    PhysicsInput,
+    PhysicsState,
+    DEFAULT_GRAVITY,
+    DEFAULT_MOVEMENT_SPEED
+};
+use crate::objects::ObjectRegistry;
+use crate::types::{GameMode, ObjectStruct};
+
+// The player is always at x 200 with no world offset
+// So an object at x 180 is right on top of the player and one at x 220 is the next block over
+// A player y of 500 is standing on the ground which puts their hitbox from y 480 to 520
+struct Scenario {
+    name: String,
+    object: ObjectStruct,
+    mode: &'static str,
+    player_y: f32,
+    velocity_y: f32,
+    jump: bool,
+    setup: fn(&mut PhysicsState),
+    expect: Outcome
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Outcome {
+    killed: bool,
+    landed: bool,
+    gravity_flipped: bool,
+    gamemode: GameMode,
+    speed: f32,
+    velocity_y: f32,
+    world_offset: f32,
+    completed: bool,
+    color_changes: usize
+}
+
+// What comes out when an object doesn't do anything to the player
+const NOTHING: Outcome = Outcome {
+    killed: false,
+    landed: false,
+    gravity_flipped: false,
+    gamemode: GameMode::Cube,
+    speed: DEFAULT_MOVEMENT_SPEED,
+    velocity_y: 0.0,
+    world_offset: 0.0,
+    completed: false,
+    color_changes: 0
+};
+
+fn object(id: u32, x: i32, y: i32, rotation: i16) -> ObjectStruct {
+    ObjectStruct {
+        y,
+        x,
+        rotation,
+        no_touch: 0,
+        hide: 0,
+        color_channel: 0,
+        id,
+        selected: false,
+        properties: None
+    }
+}
+
+fn scenario(name: &str, object: ObjectStruct, player_y: f32, expect: Outcome) -> Scenario {
+    Scenario {
+        name: name.to_string(),
+        object,
+        mode: "1",
+        player_y,
+        velocity_y: 0.0,
+        jump: false,
+        setup: |_| {},
+        expect
+    }
+}
+
+fn upside_down(state: &mut PhysicsState) {
+    state.gravity = -DEFAULT_GRAVITY;
+    state.jump_force = -state.jump_force;
+}
+
+fn run(scenario: &Scenario) -> Outcome {
+    let mut state = PhysicsState::new();
+    state.player.y = scenario.player_y;
+    state.velocity_y = scenario.velocity_y;
+    state.is_on_ground = false;
+    (scenario.setup)(&mut state);
+    update_player_hitboxes(&mut state);
+
+    let gravity_before = state.gravity;
+    hitbox_collision(
+        &scenario.object,
+        &mut state,
+        PhysicsInput { jump: scenario.jump, mouse: scenario.jump, ..Default::default() },
+        scenario.mode,
+        &ObjectRegistry::new()
+    );
+
+    Outcome {
+        killed: state.kill_player,
+        landed: state.is_on_ground,
+        gravity_flipped: (state.gravity > 0.0) != (gravity_before > 0.0),
+        gamemode: state.gamemode,
+        speed: state.movement_speed,
+        velocity_y: state.velocity_y,
+        world_offset: state.world_offset,
+        completed: state.events.contains(&PhysicsEvent::LevelComplete),
+        color_changes: state.events.iter().filter(|event| matches!(event, PhysicsEvent::ColorTrigger { .. })).count()
+    }
+}
+
+fn check(scenarios: Vec<Scenario>) {
+    let mut failures: Vec<String> = vec![];
+
+    for scenario in &scenarios {
+        let outcome = run(scenario);
+        if outcome != scenario.expect {
+            failures.push(format!(
+                "{} (id {})\n  expected {:?}\n  got      {:?}",
+                scenario.name,
+                scenario.object.id,
+                scenario.expect,
+                outcome
+            ));
+        }
+    }
+
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn spike() {
+    check(vec![
+        scenario("standing in the spike", object(1, 180, 480, 0), 500.0, Outcome { killed: true, ..NOTHING }),
+        scenario("spike in the next block", object(1, 220, 480, 0), 500.0, NOTHING),
+        // Only the middle of the spike kills so clipping the top of it is fine
+        scenario("clipping the tip of the spike", object(1, 180, 480, 0), 475.0, NOTHING),
+        scenario("spike below the player", object(1, 180, 520, 0), 500.0, NOTHING)
+    ]);
+}
+
+#[test]
+fn short_spike() {
+    check(vec![
+        scenario("standing in the short spike", object(7, 180, 480, 0), 500.0, Outcome { killed: true, ..NOTHING }),
+        // It's only the bottom of the block so jumping over the top of it is fine
+        scenario("above the short spike", object(7, 180, 480, 0), 470.0, NOTHING),
+        // Upside down it's the top of the block instead
+        scenario("upside down short spike", object(7, 180, 480, 180), 470.0, Outcome { killed: true, ..NOTHING }),
+        scenario("below the upside down short spike", object(7, 180, 480, 180), 530.0, NOTHING),
+        scenario("short spike in the next block", object(7, 220, 480, 0), 500.0, NOTHING)
+    ]);
+}
+
+#[test]
+fn blocks() {
+    let mut scenarios = vec![];
+
+    for id in [2, 10, 11, 12, 13, 14] {
+        scenarios.push(scenario("landing on top", object(id, 180, 500, 0), 500.0, Outcome { landed: true, ..NOTHING }));
+        scenarios.push(scenario(
+            "running into the side",
+            object(id, 200, 480, 0),
+            500.0,
+            Outcome { killed: true, landed: true, ..NOTHING }
+        ));
+        // Hitting the bottom of a block counts as being on the ground
+        scenarios.push(scenario("hitting the bottom", object(id, 180, 440, 0), 500.0, Outcome { landed: true, ..NOTHING }));
+        scenarios.push(scenario("block in the next column", object(id, 240, 480, 0), 500.0, NOTHING));
+
+        // Platformer blocks push you out instead of killing you
+        let mut platformer_side = scenario(
+            "running into the side in platformer",
+            object(id, 200, 480, 0),
+            500.0,
+            Outcome { world_offset: 20.0, ..NOTHING }
+        );
+        platformer_side.mode = "2";
+        scenarios.push(platformer_side);
+
+        // Holding jump while falling onto a block doesn't snap you to it
+        let mut holding_jump = scenario(
+            "holding jump on top",
+            object(id, 180, 500, 0),
+            500.0,
+            Outcome { landed: true, velocity_y: 3.0, ..NOTHING }
+        );
+        holding_jump.velocity_y = 3.0;
+        holding_jump.jump = true;
+        scenarios.push(holding_jump);
+    }
+
+    check(scenarios);
+}
+
+#[test]
+fn pads() {
+    let mut upside_down_gravity_pad = scenario(
+        "gravity pad while upside down",
+        object(21, 180, 480, 0),
+        500.0,
+        Outcome { gravity_flipped: true, velocity_y: 7.0, ..NOTHING }
+    );
+    upside_down_gravity_pad.setup = upside_down;
+
+    let mut upside_down_jump_pad = scenario(
+        "jump pad while upside down",
+        object(3, 180, 480, 0),
+        500.0,
+        Outcome { velocity_y: 15.0, ..NOTHING }
+    );
+    upside_down_jump_pad.setup = upside_down;
+
+    check(vec![
+        scenario("jump pad", object(3, 180, 480, 0), 500.0, Outcome { velocity_y: -15.0, ..NOTHING }),
+        upside_down_jump_pad,
+        scenario("jump pad in the next block", object(3, 220, 480, 0), 500.0, NOTHING),
+        // Pads are only the bottom 5 pixels of the block
+        scenario("above the jump pad", object(3, 180, 480, 0), 450.0, NOTHING),
+        scenario("gravity pad", object(21, 180, 480, 0), 500.0, Outcome { gravity_flipped: true, velocity_y: -7.0, ..NOTHING }),
+        upside_down_gravity_pad
+    ]);
+}
+
+#[test]
+fn orbs() {
+    let mut scenarios = vec![];
+
+    for (id, clicked) in [
+        (4, Outcome { velocity_y: -13.0, ..NOTHING }),
+        (22, Outcome { gravity_flipped: true, velocity_y: -7.0, ..NOTHING }),
+        (26, Outcome { killed: true, ..NOTHING })
+    ] {
+        let mut clicking = scenario("clicking on the orb", object(id, 180, 480, 0), 500.0, clicked);
+        clicking.jump = true;
+        scenarios.push(clicking);
+
+        scenarios.push(scenario("passing through without clicking", object(id, 180, 480, 0), 500.0, NOTHING));
+
+        // Orbs have a bigger hitbox than the block they are in
+        let mut clicking_nearby = scenario("clicking just outside the orb", object(id, 225, 480, 0), 500.0, clicked);
+        clicking_nearby.jump = true;
+        scenarios.push(clicking_nearby);
+
+        // Holding jump from the last orb doesn't use the next one
+        let mut still_holding = scenario("still holding from the last orb", object(id, 180, 480, 0), 500.0, NOTHING);
+        still_holding.jump = true;
+        still_holding.setup = |state| state.on_orb = false;
+        scenarios.push(still_holding);
+    }
+
+    let mut upside_down_orb = scenario("yellow orb while upside down", object(4, 180, 480, 0), 500.0, Outcome { velocity_y: 13.0, ..NOTHING });
+    upside_down_orb.jump = true;
+    upside_down_orb.setup = upside_down;
+    scenarios.push(upside_down_orb);
+
+    check(scenarios);
+}
+
+#[test]
+fn gravity_portals() {
+    let mut normal_while_upside_down = scenario(
+        "normal gravity portal while upside down",
+        object(6, 180, 480, 0),
+        500.0,
+        Outcome { gravity_flipped: true, ..NOTHING }
+    );
+    normal_while_upside_down.setup = upside_down;
+
+    let mut upside_down_twice = scenario(
+        "upside down portal while upside down",
+        object(5, 180, 480, 0),
+        500.0,
+        NOTHING
+    );
+    upside_down_twice.setup = upside_down;
+
+    check(vec![
+        scenario("upside down portal", object(5, 180, 480, 0), 500.0, Outcome { gravity_flipped: true, ..NOTHING }),
+        scenario("normal gravity portal", object(6, 180, 480, 0), 500.0, NOTHING),
+        normal_while_upside_down,
+        upside_down_twice,
+        // Portals are two blocks tall so they reach the player from the block above
+        scenario("upside down portal one block up", object(5, 180, 440, 0), 500.0, Outcome { gravity_flipped: true, ..NOTHING }),
+        scenario("upside down portal in the next block", object(5, 220, 480, 0), 500.0, NOTHING),
+        // Sideways portals are two blocks wide instead
+        scenario("sideways upside down portal", object(5, 200, 480, 90), 500.0, Outcome { gravity_flipped: true, ..NOTHING }),
+        scenario("sideways upside down portal above", object(5, 180, 420, 90), 500.0, NOTHING)
+    ]);
+}
+
+#[test]
+fn gamemode_portals() {
+    let mut cube_portal = scenario("cube portal", object(8, 180, 480, 0), 500.0, NOTHING);
+    cube_portal.setup = |state| state.gamemode = GameMode::Ship;
+
+    let mut platformer_wave_portal = scenario("wave portal in platformer", object(25, 180, 480, 0), 500.0, NOTHING);
+    platformer_wave_portal.mode = "2";
+
+    check(vec![
+        cube_portal,
+        scenario("ship portal", object(9, 180, 480, 0), 500.0, Outcome { gamemode: GameMode::Ship, ..NOTHING }),
+        scenario("ball portal", object(24, 180, 480, 0), 500.0, Outcome { gamemode: GameMode::Ball, ..NOTHING }),
+        scenario("wave portal", object(25, 180, 480, 0), 500.0, Outcome { gamemode: GameMode::Wave, ..NOTHING }),
+        // There is no wave in platformer so the portal does nothing
+        platformer_wave_portal,
+        scenario("ship portal in the next block", object(9, 220, 480, 0), 500.0, NOTHING),
+        scenario("sideways ship portal", object(9, 200, 480, 90), 500.0, Outcome { gamemode: GameMode::Ship, ..NOTHING })
+    ]);
+}
+
+#[test]
+fn speed_portals() {
+    let mut normal_speed = scenario("1x speed portal", object(17, 180, 480, 0), 500.0, NOTHING);
+    normal_speed.setup = |state| state.movement_speed = DEFAULT_MOVEMENT_SPEED * 1.8;
+
+    check(vec![
+        normal_speed,
+        scenario("2x speed portal", object(18, 180, 480, 0), 500.0, Outcome { speed: DEFAULT_MOVEMENT_SPEED * 1.4, ..NOTHING }),
+        scenario("3x speed portal", object(19, 180, 480, 0), 500.0, Outcome { speed: DEFAULT_MOVEMENT_SPEED * 1.8, ..NOTHING }),
+        scenario("0.5x speed portal", object(20, 180, 480, 0), 500.0, Outcome { speed: DEFAULT_MOVEMENT_SPEED * 0.8, ..NOTHING }),
+        scenario("3x speed portal in the next block", object(19, 220, 480, 0), 500.0, NOTHING)
+    ]);
+}
+
+#[test]
+fn end_trigger() {
+    check(vec![
+        scenario("touching the end trigger", object(15, 180, 480, 0), 500.0, Outcome { completed: true, ..NOTHING }),
+        scenario("end trigger in the next block", object(15, 220, 480, 0), 500.0, NOTHING)
+    ]);
+}
+
+#[test]
+fn color_trigger() {
+    // Turns the background red right away
+    let trigger = |x: i32| ObjectStruct {
+        properties: Some(["255", "0", "0", "1", "0", "0", "1"].iter().map(|property| property.to_string()).collect()),
+        ..object(23, x, 480, 0)
+    };
+
+    // Standing in a trigger from last tick doesn't set it off again
+    let mut still_touching = scenario("still in the color trigger", trigger(180), 500.0, NOTHING);
+    still_touching.setup = |state| state.touching_color_trigger = true;
+
+    check(vec![
+        scenario("touching the color trigger", trigger(180), 500.0, Outcome { color_changes: 1, ..NOTHING }),
+        still_touching,
+        scenario("color trigger in the next block", trigger(220), 500.0, NOTHING),
+        // A trigger that doesn't change anything it knows about does nothing
+        scenario("color trigger with no properties", object(23, 180, 480, 0), 500.0, NOTHING)
+    ]);
+}
+
+#[test]
+fn decoration() {
+    check(vec![
+        scenario("inside the black block", object(16, 180, 480, 0), 500.0, NOTHING),
+        scenario("on top of the black block", object(16, 180, 500, 0), 500.0, NOTHING),
+        scenario("inside the null object", object(0, 180, 480, 0), 500.0, NOTHING)
+    ]);
+}
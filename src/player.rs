@@ -0,0 +1,200 @@
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::colors::Rgb;
+use crate::types::GameMode;
+
+// How the player looks
+// Everyone picks their own colors and an icon for every gamemode and it gets saved in the save file
+// Icons are in Resources/icons as cube-1.png and so on
+// An icon can also have a cube-1-2.png that gets the secondary color and is drawn on top
+
+pub const ICON_FOLDER: &str = "Resources/icons";
+
+pub const GAMEMODES: [GameMode; 4] = [GameMode::Cube, GameMode::Ship, GameMode::Ball, GameMode::Wave];
+
+// Colors you can pick from on the customization screen
+pub const PLAYER_COLORS: [Rgb; 16] = [
+    (0, 228, 48),
+    (0, 158, 47),
+    (0, 255, 255),
+    (0, 121, 241),
+    (0, 82, 172),
+    (200, 122, 255),
+    (255, 0, 255),
+    (230, 41, 55),
+    (255, 161, 0),
+    (253, 249, 0),
+    (255, 203, 0),
+    (127, 106, 79),
+    (255, 255, 255),
+    (130, 130, 130),
+    (80, 80, 80),
+    (0, 0, 0)
+];
+
+pub fn gamemode_name(gamemode: GameMode) -> &'static str {
+    match gamemode {
+        GameMode::Cube => "cube",
+        GameMode::Ship => "ship",
+        GameMode::Ball => "ball",
+        GameMode::Wave => "wave"
+    }
+}
+
+pub fn gamemode_index(gamemode: GameMode) -> usize {
+    match gamemode {
+        GameMode::Cube => 0,
+        GameMode::Ship => 1,
+        GameMode::Ball => 2,
+        GameMode::Wave => 3
+    }
+}
+
+pub fn icon_path(gamemode: GameMode, icon: u16) -> String {
+    format!("{}/{}-{}.png", ICON_FOLDER, gamemode_name(gamemode), icon)
+}
+
+pub fn secondary_icon_path(gamemode: GameMode, icon: u16) -> String {
+    format!("{}/{}-{}-2.png", ICON_FOLDER, gamemode_name(gamemode), icon)
+}
+
+// Finds every icon there is for a gamemode from the file names in the icon folder
+pub fn find_icons(file_names: &[String], gamemode: GameMode) -> Vec<u16> {
+    let prefix = format!("{}-", gamemode_name(gamemode));
+
+    let mut icons: Vec<u16> = file_names.iter()
+        .filter_map(|name| name.strip_prefix(&prefix)?.strip_suffix(".png")?.parse().ok())
+        .collect();
+    icons.sort();
+    icons
+}
+
+pub fn icon_files() -> Vec<String> {
+    match fs::read_dir(ICON_FOLDER) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .collect(),
+        Err(error) => {
+            println!("Failed to find player icons: {}", error);
+            vec![]
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerLook {
+    pub primary: Rgb,
+    pub secondary: Rgb,
+    // The icon for each gamemode in the same order as GAMEMODES
+    icons: [u16; 4]
+}
+
+impl PlayerLook {
+    pub fn new() -> PlayerLook {
+        PlayerLook {
+            primary: PLAYER_COLORS[0],
+            secondary: PLAYER_COLORS[2],
+            icons: [1; 4]
+        }
+    }
+
+    pub fn icon(&self, gamemode: GameMode) -> u16 {
+        self.icons[gamemode_index(gamemode)]
+    }
+
+    pub fn set_icon(&mut self, gamemode: GameMode, icon: u16) {
+        self.icons[gamemode_index(gamemode)] = icon;
+    }
+
+    // Reads one key from an old text save file
+    // Gives back false if the key isn't about the player so the save loading can deal with it
+    // Broken values just leave the default there
+    pub fn load_save_value(&mut self, key: &str, value: &str) -> bool {
+        match key {
+            "color1" => {
+                if let Some(color) = parse_rgb(value) {
+                    self.primary = color;
+                }
+            }
+            "color2" => {
+                if let Some(color) = parse_rgb(value) {
+                    self.secondary = color;
+                }
+            }
+            "icons" => {
+                for (index, icon) in value.split(',').take(4).enumerate() {
+                    if let Ok(icon) = icon.parse() {
+                        self.icons[index] = icon;
+                    }
+                }
+            }
+            _ => return false
+        }
+
+        true
+    }
+}
+
+impl Default for PlayerLook {
+    fn default() -> PlayerLook {
+        PlayerLook::new()
+    }
+}
+
+fn parse_rgb(value: &str) -> Option<Rgb> {
+    let parts: Vec<u8> = value.split(',').map(|part| part.parse().ok()).collect::<Option<_>>()?;
+
+    match parts[..] {
+        [r, g, b] => Some((r, g, b)),
+        _ => None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn look_loads_from_old_saves() {
+        let mut look = PlayerLook::new();
+
+        assert!(look.load_save_value("color1", "1,2,3"));
+        assert!(look.load_save_value("color2", "4,5,6"));
+        assert!(look.load_save_value("icons", "1,1,1,7"));
+
+        assert_eq!((look.primary, look.secondary), ((1, 2, 3), (4, 5, 6)));
+        assert_eq!(look.icon(GameMode::Wave), 7);
+        assert_eq!(look.icon(GameMode::Cube), 1);
+    }
+
+    #[test]
+    fn broken_values_keep_the_default() {
+        let mut look = PlayerLook::new();
+
+        assert!(look.load_save_value("color1", "300,0,0"));
+        assert!(look.load_save_value("color2", "1,2"));
+        assert!(look.load_save_value("icons", "2,x"));
+        assert!(!look.load_save_value("stars", "5"));
+
+        assert_eq!(look.primary, PlayerLook::new().primary);
+        assert_eq!(look.secondary, PlayerLook::new().secondary);
+        assert_eq!(look.icon(GameMode::Cube), 2);
+        assert_eq!(look.icon(GameMode::Ship), 1);
+    }
+
+    #[test]
+    fn icons_are_found_by_file_name() {
+        let files: Vec<String> = ["cube-2.png", "cube-10.png", "cube-1.png", "cube-1-2.png", "ship-1.png", "cube-x.png", "notes.txt"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+
+        assert_eq!(find_icons(&files, GameMode::Cube), vec![1, 2, 10]);
+        assert_eq!(find_icons(&files, GameMode::Ship), vec![1]);
+        assert!(find_icons(&files, GameMode::Wave).is_empty());
+        assert_eq!(icon_path(GameMode::Ball, 3), "Resources/icons/ball-3.png");
+    }
+}
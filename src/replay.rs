@@ -0,0 +1,380 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::objects::ObjectRegistry;
+use crate::physics::{self, PhysicsEvent, PhysicsInput, PhysicsState};
+use crate::spatial::ObjectColumns;
+use crate::types::ObjectStruct;
+
+// Replays store what was held down on every physics tick of an attempt
+// Since the physics are deterministic running the same inputs on the same level gives the same run
+// The file looks like this:
+// version:1;level:<level hash>;mode:1;start:0;;;<ticks>:<buttons>;<ticks>:<buttons>;...
+// Buttons are 1 for jump, 2 for left, 4 for right, 8 for the mouse and 16 for letting go added together
+pub const REPLAY_VERSION: &str = "1";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub level_hash: u64,
+    pub mode: String,
+    pub start_offset: f32,
+    pub inputs: Vec<PhysicsInput>
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ReplayParseError {
+    MissingInputSection,
+    MissingKey(&'static str),
+    UnknownVersion(String),
+    InvalidHeader {
+        pair: String,
+        reason: String
+    },
+    InvalidInput {
+        index: usize,
+        input: String,
+        reason: String
+    }
+}
+
+// How a replay ended when it was played back without a window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayOutcome {
+    Completed { tick: usize },
+    Died { tick: usize, object: Option<usize> },
+    Unfinished { tick: usize }
+}
+
+// FNV-1a so the hash is the same on every computer and every version of rust
+// Only the things that change how the level plays go into it so recoloring a level doesn't break replays
+// Object properties are left out since right now they only say what color triggers change to
+pub fn level_hash(current_mode: &str, object_grid: &[ObjectStruct]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let mut add = |text: &str| {
+        for byte in text.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+    };
+
+    add(current_mode);
+    for object in object_grid {
+        add(&format!(
+            ";{}:{}:{}:{}:{}",
+            object.y,
+            object.x,
+            object.rotation,
+            object.no_touch,
+            object.id
+        ));
+    }
+
+    hash
+}
+
+// Where your last attempt that died gets saved when you ask for it
+const LAST_ATTEMPT_PATH: &str = "./save-data/replays/last-attempt.txt";
+
+// Where the replay for a level gets saved
+pub fn replay_path(level_hash: u64) -> String {
+    format!("./save-data/replays/{:016x}.txt", level_hash)
+}
+
+// Saves the last attempt that died if there is one
+pub fn save_last_attempt(last_attempt: &Option<Replay>) {
+    if let Some(Err(error)) = last_attempt.as_ref().map(|replay| replay.save(LAST_ATTEMPT_PATH)) {
+        println!("Failed to save your last attempt: {}", error);
+    }
+}
+
+fn input_to_buttons(input: PhysicsInput) -> u8 {
+    input.jump as u8
+    | (input.left as u8) << 1
+    | (input.right as u8) << 2
+    | (input.mouse as u8) << 3
+    | (input.released as u8) << 4
+}
+
+fn buttons_to_input(buttons: u8) -> PhysicsInput {
+    PhysicsInput {
+        jump: buttons & 1 != 0,
+        left: buttons & 2 != 0,
+        right: buttons & 4 != 0,
+        mouse: buttons & 8 != 0,
+        released: buttons & 16 != 0
+    }
+}
+
+impl Replay {
+    // Starts a new empty recording for an attempt
+    pub fn new(current_mode: &str, object_grid: &[ObjectStruct], start_offset: f32) -> Replay {
+        Replay {
+            level_hash: level_hash(current_mode, object_grid),
+            mode: current_mode.to_string(),
+            start_offset,
+            inputs: Vec::new()
+        }
+    }
+
+    pub fn parse(replay_data: &str) -> Result<Replay, ReplayParseError> {
+        let (header, inputs) = replay_data.trim().split_once(";;;")
+            .ok_or(ReplayParseError::MissingInputSection)?;
+
+        let mut version = None;
+        let mut level_hash = None;
+        let mut mode = None;
+        let mut start_offset = None;
+
+        for pair in header.split(';') {
+            let invalid = |reason: &str| ReplayParseError::InvalidHeader {
+                pair: pair.to_string(),
+                reason: reason.to_string()
+            };
+            let (key, value) = pair.split_once(':').ok_or_else(|| invalid("has no ':'"))?;
+
+            match key {
+                "version" => version = Some(value.to_string()),
+                "level" => level_hash = Some(u64::from_str_radix(value, 16).map_err(|_| invalid("is not a level hash"))?),
+                "mode" => mode = Some(value.to_string()),
+                "start" => start_offset = Some(value.parse::<f32>().map_err(|_| invalid("is not a number"))?),
+                _ => {}
+            }
+        }
+
+        let version = version.ok_or(ReplayParseError::MissingKey("version"))?;
+        if version != REPLAY_VERSION {
+            return Err(ReplayParseError::UnknownVersion(version));
+        }
+
+        let mut replay = Replay {
+            level_hash: level_hash.ok_or(ReplayParseError::MissingKey("level"))?,
+            mode: mode.ok_or(ReplayParseError::MissingKey("mode"))?,
+            start_offset: start_offset.unwrap_or(0.0),
+            inputs: Vec::new()
+        };
+
+        for (index, input) in inputs.split(';').enumerate() {
+            if input.is_empty() {
+                continue;
+            }
+
+            let invalid = |reason: &str| ReplayParseError::InvalidInput {
+                index,
+                input: input.to_string(),
+                reason: reason.to_string()
+            };
+            let (ticks, buttons) = input.split_once(':').ok_or_else(|| invalid("has no ':'"))?;
+            let ticks: usize = ticks.parse().map_err(|_| invalid("has an invalid tick count"))?;
+            let buttons: u8 = buttons.parse().map_err(|_| invalid("has invalid buttons"))?;
+            if buttons > 31 {
+                return Err(invalid("has invalid buttons"));
+            }
+
+            replay.inputs.extend(std::iter::repeat_n(buttons_to_input(buttons), ticks));
+        }
+
+        Ok(replay)
+    }
+
+    // Writes the replay to a file and makes the replays folder if it isn't there yet
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        if let Some(folder) = Path::new(path).parent() {
+            fs::create_dir_all(folder)?;
+        }
+
+        fs::write(path, self.to_string())
+    }
+
+    // What to hold down on a tick
+    // Once the replay runs out nothing is held
+    pub fn input(&self, tick: usize) -> PhysicsInput {
+        self.inputs.get(tick).copied().unwrap_or_default()
+    }
+
+    // The state the player starts the replay in
+    pub fn start_state(&self) -> PhysicsState {
+        let mut state = PhysicsState::new();
+        state.world_offset = self.start_offset;
+        state
+    }
+
+    // Runs the replay without a window until it beats the level, dies or runs out of inputs
+    pub fn play(&self, object_grid: &[ObjectStruct], registry: &ObjectRegistry) -> ReplayOutcome {
+        let mut state = self.start_state();
+        let object_columns = ObjectColumns::new(object_grid);
+
+        for tick in 0..self.inputs.len() {
+            physics::step(&mut state, self.input(tick), object_grid, &object_columns, &self.mode, registry);
+
+            if state.events.contains(&PhysicsEvent::LevelComplete) {
+                return ReplayOutcome::Completed { tick };
+            }
+
+            if state.kill_player {
+                return ReplayOutcome::Died { tick, object: state.killed_by };
+            }
+        }
+
+        ReplayOutcome::Unfinished { tick: self.inputs.len() }
+    }
+}
+
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "version:{};level:{:016x};mode:{};start:{};;;",
+            REPLAY_VERSION,
+            self.level_hash,
+            self.mode,
+            self.start_offset
+        )?;
+
+        // Runs of the same buttons get stored as one entry so replays stay small
+        let mut runs: Vec<String> = vec![];
+        let mut index = 0;
+        while index < self.inputs.len() {
+            let buttons = input_to_buttons(self.inputs[index]);
+            let mut ticks = 0;
+            while index < self.inputs.len() && input_to_buttons(self.inputs[index]) == buttons {
+                ticks += 1;
+                index += 1;
+            }
+
+            runs.push(format!("{}:{}", ticks, buttons));
+        }
+
+        write!(f, "{}", runs.join(";"))
+    }
+}
+
+impl fmt::Display for ReplayParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayParseError::MissingInputSection => write!(f, "Replay has no inputs (missing ';;;')"),
+            ReplayParseError::MissingKey(key) => write!(f, "Replay is missing '{}'", key),
+            ReplayParseError::UnknownVersion(version) => write!(f, "Replay version '{}' is not supported", version),
+            ReplayParseError::InvalidHeader { pair, reason } => write!(f, "Replay info '{}' {}", pair, reason),
+            ReplayParseError::InvalidInput { index, input, reason } => write!(f, "Replay input {} '{}' {}", index, input, reason)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(x: i32, y: i32, id: u32) -> ObjectStruct {
+        ObjectStruct {
+            y,
+            x,
+            rotation: 0,
+            no_touch: 0,
+            hide: 0,
+            color_channel: 0,
+            id,
+            selected: false,
+            properties: None
+        }
+    }
+
+    fn replay(inputs: Vec<PhysicsInput>, object_grid: &[ObjectStruct]) -> Replay {
+        let mut replay = Replay::new("1", object_grid, 0.0);
+        replay.inputs = inputs;
+        replay
+    }
+
+    #[test]
+    fn replay_round_trips() {
+        let jump = PhysicsInput { jump: true, ..Default::default() };
+        let right = PhysicsInput { right: true, ..Default::default() };
+        let released = PhysicsInput { released: true, ..Default::default() };
+        let original = replay(vec![PhysicsInput::default(), jump, jump, right, released], &[]);
+        let replay_data = original.to_string();
+
+        assert!(replay_data.ends_with(";;;1:0;2:1;1:4;1:16"));
+        assert_eq!(Replay::parse(&replay_data), Ok(original));
+    }
+
+    #[test]
+    fn mouse_and_letting_go_are_saved() {
+        let clicking = PhysicsInput { jump: true, mouse: true, ..Default::default() };
+        let let_go = PhysicsInput { right: true, released: true, ..Default::default() };
+        let original = replay(vec![clicking, let_go], &[]);
+        let replay_data = original.to_string();
+
+        assert!(replay_data.ends_with(";;;1:9;1:20"));
+        assert_eq!(Replay::parse(&replay_data), Ok(original));
+        assert!(Replay::parse("version:1;level:ff;mode:1;;;1:32").is_err());
+    }
+
+    #[test]
+    fn bad_inputs_are_reported() {
+        assert_eq!(
+            Replay::parse("version:1;level:ff;mode:1;;;3:0;x:1"),
+            Err(ReplayParseError::InvalidInput {
+                index: 1,
+                input: "x:1".to_string(),
+                reason: "has an invalid tick count".to_string()
+            })
+        );
+        assert_eq!(Replay::parse("version:1;mode:1;;;"), Err(ReplayParseError::MissingKey("level")));
+    }
+
+    #[test]
+    fn level_hash_only_changes_with_gameplay() {
+        let level = vec![object(400, 480, 1)];
+        let mut hidden = level.clone();
+        hidden[0].hide = 1;
+        let mut moved = level.clone();
+        moved[0].x += 1;
+        let mut rotated = level.clone();
+        rotated[0].rotation = 90;
+        let mut no_touch = level.clone();
+        no_touch[0].no_touch = 1;
+
+        assert_eq!(level_hash("1", &level), level_hash("1", &hidden));
+        assert_ne!(level_hash("1", &level), level_hash("1", &rotated));
+        assert_ne!(level_hash("1", &level), level_hash("1", &no_touch));
+        assert_ne!(level_hash("1", &level), level_hash("1", &moved));
+        assert_ne!(level_hash("1", &level), level_hash("2", &level));
+    }
+
+    #[test]
+    fn recoloring_a_level_keeps_its_hash() {
+        let mut trigger = object(400, 480, 23);
+        trigger.properties = Some(["255", "0", "0", "1", "0", "0", "1"].iter().map(|property| property.to_string()).collect());
+        let level = vec![trigger];
+
+        let mut recolored = level.clone();
+        recolored[0].properties = Some(["0", "0", "255", "3", "1.5", "2", "4"].iter().map(|property| property.to_string()).collect());
+        let mut no_properties = level.clone();
+        no_properties[0].properties = None;
+
+        assert_eq!(level_hash("1", &level), level_hash("1", &recolored));
+        assert_eq!(level_hash("1", &level), level_hash("1", &no_properties));
+    }
+
+    #[test]
+    fn replay_reaches_the_end_trigger() {
+        let level = vec![object(1000, 480, 15)];
+
+        let outcome = replay(vec![PhysicsInput::default(); 300], &level).play(&level, &ObjectRegistry::new());
+        assert!(matches!(outcome, ReplayOutcome::Completed { .. }));
+    }
+
+    #[test]
+    fn jumping_over_a_spike_beats_the_level() {
+        let level = vec![object(600, 480, 1), object(1000, 480, 15)];
+        let mut inputs = vec![PhysicsInput::default(); 300];
+
+        assert!(matches!(replay(inputs.clone(), &level).play(&level, &ObjectRegistry::new()), ReplayOutcome::Died { .. }));
+
+        // The spike gets to the player after about 66 ticks and a jump lasts about 32
+        for input in &mut inputs[50..54] {
+            input.jump = true;
+        }
+        assert!(matches!(replay(inputs, &level).play(&level, &ObjectRegistry::new()), ReplayOutcome::Completed { .. }));
+    }
+}
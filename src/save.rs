@@ -0,0 +1,315 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::account::Session;
+use crate::player::PlayerLook;
+
+// Your stars, account and what you have beaten
+// It gets saved as json with a version so new stuff can be added without breaking older saves
+// Saves from before this were stars:0;user:0;pass:0;;;0:0;1:0;;;0 and get turned into the new format when they are loaded
+// Only you can read the save file since it has your login token in it
+
+pub const SAVE_PATH: &str = "./save-data/save.txt";
+
+// The first json save is version 1 so the old text saves are version 0
+pub const SAVE_VERSION: u64 = 1;
+
+// Each step upgrades a save from one version to the next one
+// SAVE_MIGRATIONS[0] turns a version 1 save into a version 2 save and so on
+const SAVE_MIGRATIONS: [fn(&mut Value); 0] = [];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveData {
+    pub version: u64,
+    pub stars: u32,
+    // Who you are logged in as
+    // Logins without a token don't get saved since there is nothing to log back in with
+    #[serde(skip_serializing_if = "has_no_token")]
+    pub session: Option<Session>,
+    // One for every main level in order
+    pub main_levels_completed: Vec<bool>,
+    // The ids of the rated online levels you got stars from
+    pub online_levels_beaten: Vec<u16>,
+    pub player: PlayerLook
+}
+
+fn has_no_token(session: &Option<Session>) -> bool {
+    session.as_ref().is_none_or(|session| session.token.is_none())
+}
+
+impl Default for SaveData {
+    fn default() -> SaveData {
+        SaveData {
+            version: SAVE_VERSION,
+            stars: 0,
+            session: None,
+            main_levels_completed: vec![],
+            online_levels_beaten: vec![],
+            player: PlayerLook::new()
+        }
+    }
+}
+
+impl SaveData {
+    pub fn to_text(&self) -> String {
+        let mut save = self.clone();
+        save.version = SAVE_VERSION;
+
+        serde_json::to_string_pretty(&save).expect("Save data can always be turned into json")
+    }
+}
+
+// Works out what kind of save it is and turns it into the newest version
+pub fn parse_save(text: &str) -> Result<SaveData, String> {
+    if !text.trim_start().starts_with('{') {
+        return parse_old_save(text);
+    }
+
+    let mut value: Value = serde_json::from_str(text).map_err(|error| format!("Save file is broken: {}", error))?;
+    let version = value.get("version").and_then(Value::as_u64).ok_or("Save file has no version")?;
+
+    if version == 0 {
+        return Err("Save file has version 0 which only old text saves can have".to_string());
+    }
+
+    if version > SAVE_VERSION {
+        println!("Save file is from a newer version of the game so some of it might not load");
+    }
+
+    for migration in SAVE_MIGRATIONS.iter().skip(version as usize - 1) {
+        migration(&mut value);
+    }
+
+    let mut save: SaveData = serde_json::from_value(value).map_err(|error| format!("Save file is broken: {}", error))?;
+    save.version = SAVE_VERSION;
+    Ok(save)
+}
+
+// The old text saves
+// The last part always starts with a 0 that isn't a level so it gets skipped
+fn parse_old_save(text: &str) -> Result<SaveData, String> {
+    let sections: Vec<&str> = text.split(";;;").collect();
+    if sections.len() != 3 {
+        return Err(format!("Save file has {} parts instead of 3", sections.len()));
+    }
+
+    let mut save = SaveData::default();
+
+    for pair in sections[0].split(';').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once(':').ok_or(format!("'{}' in the save file has no value", pair))?;
+
+        if save.player.load_save_value(key, value) {
+            continue;
+        }
+
+        // The user and pass get thrown away and you have to log in again to get a token
+        if key == "stars" {
+            save.stars = value.parse().map_err(|_| format!("'{}' isn't a number of stars", value))?;
+        }
+    }
+
+    for level in sections[1].split(';').filter(|level| !level.is_empty()) {
+        match level.split_once(':') {
+            Some((_, "1")) => save.main_levels_completed.push(true),
+            Some((_, "0")) => save.main_levels_completed.push(false),
+            _ => return Err(format!("'{}' isn't a main level in the save file", level))
+        }
+    }
+
+    for id in sections[2].split(';').skip(1).filter(|id| !id.is_empty()) {
+        save.online_levels_beaten.push(id.parse().map_err(|_| format!("'{}' isn't an online level id", id))?);
+    }
+
+    Ok(save)
+}
+
+fn with_extension(path: &Path, extension: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(extension);
+    PathBuf::from(path)
+}
+
+fn read_save(path: &Path) -> Result<SaveData, String> {
+    let text = fs::read_to_string(path).map_err(|error| format!("Failed to read {}: {}", path.display(), error))?;
+    parse_save(&text)
+}
+
+// Loads the save and falls back to the backup if it's broken
+// If neither of them work you start with a new save instead of the game crashing
+pub fn load_save(path: &Path) -> SaveData {
+    let backup_path = with_extension(path, ".bak");
+
+    if !path.exists() && !backup_path.exists() {
+        return SaveData::default();
+    }
+
+    match read_save(path) {
+        Ok(save) => return save,
+        Err(error) => println!("{}", error)
+    }
+
+    match read_save(&backup_path) {
+        Ok(save) => {
+            println!("Loaded the backup save instead");
+            save
+        }
+        Err(error) => {
+            println!("{}", error);
+            println!("Starting with a new save");
+            SaveData::default()
+        }
+    }
+}
+
+// Writes to a temporary file first and then swaps it in
+// That way the game closing in the middle of saving can't leave a half written file
+pub fn write_file_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    replace_file(path, contents, false)
+}
+
+fn replace_file(path: &Path, contents: &str, private: bool) -> io::Result<()> {
+    let temp_path = with_extension(path, ".tmp");
+    let _ = fs::remove_file(&temp_path);
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(&temp_path)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(&temp_path, path)
+}
+
+// The last save that worked gets kept as the backup
+// It gets written again instead of copied so anything an upgrade threw away like old passwords doesn't stay in the backup
+pub fn write_save(path: &Path, save: &SaveData) -> io::Result<()> {
+    if let Ok(last_save) = read_save(path) {
+        replace_file(&with_extension(path, ".bak"), &last_save.to_text(), true)?;
+    }
+
+    replace_file(path, &save.to_text(), true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::GameMode;
+
+    fn temp_save_path(name: &str) -> PathBuf {
+        let folder = std::env::temp_dir().join(format!("geometry-rays-save-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&folder);
+        fs::create_dir_all(&folder).unwrap();
+        folder.join("save.txt")
+    }
+
+    #[test]
+    fn old_saves_get_upgraded() {
+        let save = parse_save("stars:12;user:Puppet;pass:hunter2;color1:1,2,3;icons:2,1,1,1;;;0:1;1:0;2:1;;;0;15;200").unwrap();
+
+        assert_eq!(save.version, SAVE_VERSION);
+        assert_eq!(save.stars, 12);
+        assert_eq!(save.session, None);
+        assert_eq!(save.main_levels_completed, vec![true, false, true]);
+        assert_eq!(save.online_levels_beaten, vec![15, 200]);
+        assert_eq!(save.player.primary, (1, 2, 3));
+        assert_eq!(save.player.icon(GameMode::Cube), 2);
+
+        // The save that comes with the game
+        let save = parse_save(include_str!("../save-data/save.txt")).unwrap();
+        assert_eq!(save.session, None);
+        assert_eq!(save.main_levels_completed, vec![false; 5]);
+        assert!(save.online_levels_beaten.is_empty());
+    }
+
+    #[test]
+    fn saves_round_trip() {
+        let mut save = SaveData::default();
+        save.stars = 40;
+        save.session = Some(Session { user: "someone".to_string(), token: Some("abc".to_string()) });
+        save.main_levels_completed = vec![true, true];
+        save.online_levels_beaten = vec![3];
+        save.player.set_icon(GameMode::Ball, 3);
+
+        assert_eq!(parse_save(&save.to_text()), Ok(save));
+    }
+
+    #[test]
+    fn logins_without_a_token_dont_get_saved() {
+        let save = SaveData {
+            session: Some(Session { user: "someone".to_string(), token: None }),
+            ..SaveData::default()
+        };
+
+        assert!(!save.to_text().contains("someone"));
+        assert_eq!(parse_save(&save.to_text()).map(|save| save.session), Ok(None));
+    }
+
+    #[test]
+    fn broken_saves_are_errors() {
+        assert!(parse_save("stars:12;user:0;pass:0;;;0:1").is_err());
+        assert!(parse_save("stars:lots;user:0;pass:0;;;;;0").is_err());
+        assert!(parse_save("{\"version\": 1, \"stars\": ").is_err());
+        assert!(parse_save("{\"stars\": 5}").is_err());
+        // Missing keys are fine since newer versions add them
+        assert_eq!(parse_save("{\"version\": 1, \"stars\": 5}").map(|save| save.stars), Ok(5));
+    }
+
+    #[test]
+    fn backup_is_used_when_the_save_is_broken() {
+        let path = temp_save_path("backup");
+        assert_eq!(load_save(&path), SaveData::default());
+
+        let mut save = SaveData::default();
+        save.stars = 1;
+        write_save(&path, &save).unwrap();
+        save.stars = 2;
+        write_save(&path, &save).unwrap();
+        assert_eq!(load_save(&path).stars, 2);
+        assert!(!with_extension(&path, ".tmp").exists());
+
+        // Like the game got closed halfway through writing the save
+        fs::write(&path, "{\"version\": 1, \"sta").unwrap();
+        assert_eq!(load_save(&path).stars, 1);
+
+        // A broken save doesn't replace the backup
+        write_save(&path, &save).unwrap();
+        assert_eq!(read_save(&with_extension(&path, ".bak")).map(|save| save.stars), Ok(1));
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn passwords_dont_get_kept() {
+        let path = temp_save_path("passwords");
+        fs::write(&path, "stars:3;user:someone;pass:hunter2;;;0:0;;;0").unwrap();
+
+        let save = load_save(&path);
+        assert_eq!((save.stars, save.session.clone()), (3, None));
+        write_save(&path, &save).unwrap();
+
+        for file in [path.clone(), with_extension(&path, ".bak")] {
+            assert!(!fs::read_to_string(&file).unwrap().contains("hunter2"));
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                assert_eq!(fs::metadata(&file).unwrap().permissions().mode() & 0o777, 0o600);
+            }
+        }
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
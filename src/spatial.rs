@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use crate::types::ObjectStruct;
+
+// Levels can have thousands of objects but only the ones near the player or the camera matter
+// So objects get put into columns by their x and only the columns that are needed get looked at
+pub const COLUMN_WIDTH: i32 = 200;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectColumns {
+    column_width: i32,
+    // Every column has the x and index of the objects in it
+    columns: BTreeMap<i32, Vec<(i32, usize)>>
+}
+
+impl ObjectColumns {
+    pub fn new(object_grid: &[ObjectStruct]) -> ObjectColumns {
+        ObjectColumns::with_column_width(object_grid, COLUMN_WIDTH)
+    }
+
+    // A huge column width puts everything in one column which is the same as checking every object
+    // The benchmark uses that to compare against
+    pub fn with_column_width(object_grid: &[ObjectStruct], column_width: i32) -> ObjectColumns {
+        let mut columns: BTreeMap<i32, Vec<(i32, usize)>> = BTreeMap::new();
+
+        for (index, object) in object_grid.iter().enumerate() {
+            columns.entry(object.x.div_euclid(column_width))
+                .or_default()
+                .push((object.x, index));
+        }
+
+        ObjectColumns { column_width, columns }
+    }
+
+    // The indexes of every object with an x from min_x to max_x
+    // They come back in the same order as the level so collision and drawing work the same as going through every object
+    pub fn in_range(&self, min_x: i32, max_x: i32) -> Vec<usize> {
+        if min_x > max_x {
+            return vec![];
+        }
+
+        let mut indexes: Vec<usize> = self.columns
+            .range(min_x.div_euclid(self.column_width)..=max_x.div_euclid(self.column_width))
+            .flat_map(|(_, objects)| objects)
+            .filter(|(x, _)| *x >= min_x && *x <= max_x)
+            .map(|(_, index)| *index)
+            .collect();
+
+        indexes.sort_unstable();
+        indexes
+    }
+
+    // The objects that could be under a point in the level
+    // Objects are one grid cell big so anything from 40 to the left up to the point could be touching it
+    pub fn near_point(&self, x: i32) -> Vec<usize> {
+        self.in_range(x - 40, x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(x: i32) -> ObjectStruct {
+        ObjectStruct {
+            y: 480,
+            x,
+            rotation: 0,
+            no_touch: 0,
+            hide: 0,
+            color_channel: 0,
+            id: 1,
+            selected: false,
+            properties: None
+        }
+    }
+
+    #[test]
+    fn range_matches_checking_every_object() {
+        let level: Vec<ObjectStruct> = [-410, -40, 0, 39, 199, 200, 201, 640, 1000, 5, 400]
+            .into_iter()
+            .map(object)
+            .collect();
+        let columns = ObjectColumns::new(&level);
+
+        for (min_x, max_x) in [(-500, 2000), (0, 200), (-40, 39), (201, 639), (641, 999), (10, 5)] {
+            let expected: Vec<usize> = (0..level.len())
+                .filter(|index| level[*index].x >= min_x && level[*index].x <= max_x)
+                .collect();
+            assert_eq!(columns.in_range(min_x, max_x), expected, "{} to {}", min_x, max_x);
+        }
+    }
+}
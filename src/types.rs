@@ -1,11 +1,16 @@
 use raylib::prelude::*;
 
+use crate::colors::ColorChannels;
+use crate::inspector::InspectorField;
+use crate::level_info::LevelInfo;
+
 #[derive(PartialEq)]
 pub enum GameState {
     Menu,
     Playing,
     GameOver,
     CreatorMenu,
+    LevelLibrary,
     Editor,
     LevelOptions,
     LevelSelect,
@@ -17,6 +22,7 @@ pub enum GameState {
     SearchPage,
     LevelRate,
     OptionsMenu,
+    PlayerCustomization,
 }
 
 #[derive(PartialEq)]
@@ -45,7 +51,7 @@ pub struct MainLevel {
     pub data: String
 }
 
-#[derive(PartialEq)]
+#[derive(Debug, PartialEq)]
 #[derive(Clone, Copy)]
 pub enum GameMode {
     Cube,
@@ -63,7 +69,16 @@ pub enum EditorTab {
     Delete
 }
 
-#[derive(Debug)]
+// What selecting does to the objects that are already selected
+#[derive(PartialEq)]
+#[derive(Clone, Copy)]
+pub enum SelectMode {
+    Replace,
+    Add,
+    Subtract
+}
+
+#[derive(Debug, Clone, PartialEq)]
 #[allow(dead_code)]
 pub struct ObjectStruct {
     pub y: i32,
@@ -71,6 +86,8 @@ pub struct ObjectStruct {
     pub rotation: i16,
     pub no_touch: u8,
     pub hide: u8,
+    // 0 means the object uses the normal object color
+    pub color_channel: u16,
     pub id: u32,
     pub selected: bool,
     pub properties: Option<Vec<String>>
@@ -82,10 +99,76 @@ pub struct TextBox {
     pub text_size: u8,
     pub max_length: u8,
     pub spaces_allowed: bool,
+    // Lets you type - and . for numbers that can be negative or have decimals
+    pub symbols_allowed: bool,
     pub active: bool
 }
 
+// One row in the object settings popup
+// Numbers get typed into the textbox and toggles and choices are a button you click to change
+pub struct InspectorRow {
+    pub field: InspectorField,
+    pub textbox: TextBox,
+    pub text: String,
+    pub button: Button
+}
+
+// One of the player icons that got loaded from Resources/icons
+// The secondary part is optional so icons with only one color still work
+pub struct PlayerIcon {
+    pub icon: u16,
+    pub texture: Texture2D,
+    pub secondary: Option<Texture2D>
+}
+
 pub struct ObjButton {
     pub btn: Button,
-    pub obj_id: u32
-}
\ No newline at end of file
+    pub obj_id: u32,
+    pub page: usize
+}
+
+// The data model for a level
+// Every metadata key is optional because older levels dont have all of them
+// key_order remembers what order the keys were in so saving a level gives back the exact same string
+#[derive(Debug, Clone, PartialEq)]
+pub struct Level {
+    pub version: String,
+    pub mode: Option<String>,
+    pub song: Option<u8>,
+    pub c1001: Option<(u8, u8, u8)>,
+    pub c1002: Option<(u8, u8, u8)>,
+    pub c1004: Option<(u8, u8, u8)>,
+    // c1 to c999
+    pub channels: ColorChannels,
+    pub bg: Option<u8>,
+    pub grnd: Option<u8>,
+    // The name, description and such
+    pub info: LevelInfo,
+    pub extra: Vec<(String, String)>,
+    pub key_order: Vec<String>,
+    pub objects: Vec<ObjectStruct>
+}
+
+// Everything that can go wrong while loading a level
+// Offsets are counted in characters from the start of the level string so they match what you see in a text editor
+#[derive(Debug, PartialEq)]
+pub enum LevelParseError {
+    MissingObjectSection,
+    MissingVersion,
+    UnknownVersion(String),
+    InvalidMetadata {
+        offset: usize,
+        pair: String,
+        reason: String
+    },
+    InvalidObject {
+        index: usize,
+        offset: usize,
+        field: &'static str,
+        reason: String
+    },
+    InvalidResponse {
+        field: &'static str,
+        reason: String
+    }
+}
@@ -0,0 +1,203 @@
+use std::fs;
+
+use crate::migrations::upgrade_level;
+use crate::objects::ObjectRegistry;
+use crate::physics::TICKS_PER_SECOND;
+use crate::replay::{self, Replay, ReplayOutcome};
+use crate::types::{Level, ObjectStruct};
+
+// Checks that a replay beats a level starting from the beginning
+// Gives back the tick the end trigger was hit on or a message saying why it wasn't
+pub fn verify_replay(
+    replay: &Replay,
+    current_mode: &str,
+    object_grid: &[ObjectStruct],
+    registry: &ObjectRegistry
+) -> Result<usize, String> {
+    if replay.level_hash != replay::level_hash(current_mode, object_grid) {
+        return Err("Replay was recorded on a different level".to_string());
+    }
+
+    // Playtesting from a start position further into the level doesn't count
+    if replay.start_offset < 0.0 {
+        return Err("Replay starts part way through the level".to_string());
+    }
+
+    match replay.play(object_grid, registry) {
+        ReplayOutcome::Completed { tick } => Ok(tick),
+        ReplayOutcome::Died { tick, object: Some(index) } => {
+            let object = &object_grid[index];
+            let name = registry.name(object.id);
+
+            Err(format!(
+                "Player died on tick {} to object {} ({} id {} at x {} y {})",
+                tick,
+                index,
+                name,
+                object.id,
+                object.x,
+                object.y
+            ))
+        }
+        ReplayOutcome::Died { tick, object: None } => Err(format!("Player died on tick {}", tick)),
+        ReplayOutcome::Unfinished { tick } => Err(format!("Replay ran out on tick {} without reaching the end trigger (id 15)", tick))
+    }
+}
+
+// Verifies the replay the game saved the last time this level was beaten
+pub fn verify_saved_replay(
+    current_mode: &str,
+    object_grid: &[ObjectStruct],
+    registry: &ObjectRegistry
+) -> Result<usize, String> {
+    let replay_path = replay::replay_path(replay::level_hash(current_mode, object_grid));
+    let replay_data = fs::read_to_string(&replay_path)
+        .map_err(|_| "Beat your level from the start before uploading it".to_string())?;
+    let replay = Replay::parse(&replay_data).map_err(|error| error.to_string())?;
+
+    verify_replay(&replay, current_mode, object_grid, registry)
+}
+
+// This is what runs when you do geometry-rays verify <level> <replay>
+// It gives back the exit code
+pub fn run(args: &[String]) -> i32 {
+    if args.len() != 2 {
+        println!("Usage: geometry-rays verify <level> <replay>");
+        return 2
+    }
+
+    let level_path = &args[0];
+    let replay_path = &args[1];
+
+    let level = match fs::read_to_string(level_path) {
+        Ok(level_data) => Level::parse(&level_data).map_err(|error| error.to_string()),
+        Err(error) => Err(format!("Failed to read file: {}", error))
+    };
+    let level = match level {
+        Ok(level) => upgrade_level(level),
+        Err(error) => {
+            println!("{}: {}", level_path, error);
+            return 2
+        }
+    };
+
+    let replay = match fs::read_to_string(replay_path) {
+        Ok(replay_data) => Replay::parse(&replay_data).map_err(|error| error.to_string()),
+        Err(error) => Err(format!("Failed to read file: {}", error))
+    };
+    let replay = match replay {
+        Ok(replay) => replay,
+        Err(error) => {
+            println!("{}: {}", replay_path, error);
+            return 2
+        }
+    };
+
+    let current_mode = level.mode.unwrap_or("1".to_string());
+    match verify_replay(&replay, &current_mode, &level.objects, &ObjectRegistry::with_mods()) {
+        Ok(tick) => {
+            println!(
+                "{}: beaten on tick {} ({:.2} seconds)",
+                level_path,
+                tick,
+                tick as f32 / TICKS_PER_SECOND
+            );
+            0
+        }
+        Err(problem) => {
+            println!("{}: {}", level_path, problem);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::PhysicsInput;
+
+    fn object(x: i32, y: i32, id: u32) -> ObjectStruct {
+        ObjectStruct {
+            y,
+            x,
+            rotation: 0,
+            no_touch: 0,
+            hide: 0,
+            color_channel: 0,
+            id,
+            selected: false,
+            properties: None
+        }
+    }
+
+    fn replay(inputs: Vec<PhysicsInput>, object_grid: &[ObjectStruct]) -> Replay {
+        let mut replay = Replay::new("1", object_grid, 0.0);
+        replay.inputs = inputs;
+        replay
+    }
+
+    #[test]
+    fn beating_the_level_passes() {
+        let level = vec![object(1000, 480, 15)];
+        let replay = replay(vec![PhysicsInput::default(); 300], &level);
+
+        let tick = verify_replay(&replay, "1", &level, &ObjectRegistry::new()).unwrap();
+        assert!(tick > 0 && tick < 300);
+    }
+
+    #[test]
+    fn replays_from_other_levels_are_rejected() {
+        let level = vec![object(1000, 480, 15)];
+        let other_level = vec![object(1040, 480, 15)];
+        let replay = replay(vec![PhysicsInput::default(); 300], &other_level);
+
+        assert_eq!(
+            verify_replay(&replay, "1", &level, &ObjectRegistry::new()),
+            Err("Replay was recorded on a different level".to_string())
+        );
+        assert_eq!(
+            verify_replay(&replay, "2", &other_level, &ObjectRegistry::new()),
+            Err("Replay was recorded on a different level".to_string())
+        );
+    }
+
+    #[test]
+    fn dying_says_what_killed_the_player() {
+        let level = vec![object(600, 480, 1), object(1000, 480, 15)];
+        let replay = replay(vec![PhysicsInput::default(); 300], &level);
+        let registry = ObjectRegistry::new();
+
+        let tick = match replay.play(&level, &registry) {
+            ReplayOutcome::Died { tick, object: Some(0) } => tick,
+            outcome => panic!("expected the spike to kill the player but got {:?}", outcome)
+        };
+
+        assert_eq!(
+            verify_replay(&replay, "1", &level, &registry),
+            Err(format!("Player died on tick {} to object 0 (spike id 1 at x 600 y 480)", tick))
+        );
+    }
+
+    #[test]
+    fn running_out_of_inputs_is_rejected() {
+        let level = vec![object(1000, 480, 15)];
+        let replay = replay(vec![PhysicsInput::default(); 10], &level);
+
+        assert_eq!(
+            verify_replay(&replay, "1", &level, &ObjectRegistry::new()),
+            Err("Replay ran out on tick 10 without reaching the end trigger (id 15)".to_string())
+        );
+    }
+
+    #[test]
+    fn starting_part_way_through_is_rejected() {
+        let level = vec![object(1000, 480, 15)];
+        let mut replay = replay(vec![PhysicsInput::default(); 300], &level);
+        replay.start_offset = -400.0;
+
+        assert_eq!(
+            verify_replay(&replay, "1", &level, &ObjectRegistry::new()),
+            Err("Replay starts part way through the level".to_string())
+        );
+    }
+}